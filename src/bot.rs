@@ -0,0 +1,11 @@
+//Computer players, each of them usable as a seat of the game
+mod heuristic;
+mod ismcts;
+mod pimc;
+mod random;
+mod tracking;
+
+pub use heuristic::{hand_strength, HeuristicBot};
+pub use ismcts::{IsmctsBot, DEFAULT_ITERATIONS};
+pub use pimc::{PimcBot, DEFAULT_SAMPLES, DEFAULT_TIME_BUDGET};
+pub use random::RandomBot;
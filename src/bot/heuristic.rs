@@ -0,0 +1,270 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+use crate::controller::{Answer, PlayerController};
+use crate::game::SeatView;
+
+//Below this strength the choice is pushed to the partner
+const SCHIEBEN_BELOW: u32 = 30;
+
+//Plays by simple rules of thumb, the way a casual player would
+pub struct HeuristicBot;
+
+impl HeuristicBot {
+    pub fn new() -> HeuristicBot {
+        HeuristicBot
+    }
+}
+
+impl Default for HeuristicBot {
+    fn default() -> HeuristicBot {
+        HeuristicBot::new()
+    }
+}
+
+//How good a hand is to play in a mode, roughly the number of points it should bring in
+pub fn hand_strength(hand: CardSet, mode: GameMode) -> u32 {
+    let has = |suit: Suit, number: Number| hand.contains(Card { suit, number });
+    let count = |suit: Suit| hand.suit(suit).len() as u32;
+    match mode {
+        GameMode::Trump(trump) => {
+            let mut strength = 4 * count(trump);
+            if has(trump, Number::Jack) {
+                strength += 16; //Bour
+            }
+            if has(trump, Number::Nine) {
+                strength += 10; //Nell
+            }
+            if has(trump, Number::Ace) {
+                strength += 4;
+            }
+            //Aces on the side win folds once trumps are gone
+            strength
+                + 3 * ALL_SUITS
+                    .iter()
+                    .filter(|&&s| s != trump && has(s, Number::Ace))
+                    .count() as u32
+        }
+        GameMode::Obenabe | GameMode::Undenufe => {
+            let (top, second) = match mode {
+                GameMode::Obenabe => (Number::Ace, Number::King),
+                _ => (Number::Six, Number::Seven),
+            };
+            ALL_SUITS
+                .iter()
+                .map(|&s| match (has(s, top), has(s, second)) {
+                    (true, true) => 14 + 2 * count(s),
+                    (true, false) => 8,
+                    _ => 0,
+                })
+                .sum()
+        }
+    }
+}
+
+fn best_mode(hand: CardSet) -> (GameMode, u32) {
+    ALL_MODES
+        .iter()
+        .map(|&mode| (mode, hand_strength(hand, mode)))
+        .max_by_key(|&(_, strength)| strength)
+        .expect("there are modes to choose")
+}
+
+impl PlayerController for HeuristicBot {
+    fn schieben(&mut self, view: &SeatView) -> Answer<bool> {
+        Ok(best_mode(view.hand()).1 < SCHIEBEN_BELOW)
+    }
+
+    fn choose_trump(&mut self, view: &SeatView) -> Answer<GameMode> {
+        Ok(best_mode(view.hand()).0)
+    }
+
+    fn choose_card(&mut self, view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+        let mode = view.mode.expect("trump is chosen before playing");
+        Ok(match view.bottom() {
+            None => lead(view, legal_cards, mode),
+            Some(bottom) => follow(view, legal_cards, mode, bottom),
+        })
+    }
+}
+
+fn lead(view: &SeatView, legal_cards: &[Card], mode: GameMode) -> Card {
+    if let Some(trump) = mode.trump() {
+        let trumps: Vec<&Card> = legal_cards.iter().filter(|c| c.suit == trump).collect();
+        let played_trumps = view
+            .tricks
+            .iter()
+            .flat_map(|t| t.cards.iter())
+            .filter(|x| x.card.suit == trump)
+            .count();
+        //With the Bour or the Nell and enough trumps, pull the trumps of the opponents
+        let strong = trumps.len() >= 3
+            && trumps
+                .iter()
+                .any(|c| c.number == Number::Jack || c.number == Number::Nine);
+        if strong && played_trumps + trumps.len() < 9 {
+            return **trumps
+                .iter()
+                .max_by_key(|c| c.power(mode, trump))
+                .expect("there are trumps");
+        }
+    }
+    //Otherwise cash the strongest side card if it is the top of its suit, or lead low
+    let side: Vec<&Card> = legal_cards
+        .iter()
+        .filter(|c| Some(c.suit) != mode.trump())
+        .collect();
+    let candidates = if side.is_empty() {
+        legal_cards.iter().collect()
+    } else {
+        side
+    };
+    let top = candidates
+        .iter()
+        .max_by_key(|c| c.power(mode, c.suit))
+        .expect("there is always a legal card");
+    if top.power(mode, top.suit) == 9 {
+        return **top;
+    }
+    **candidates
+        .iter()
+        .min_by_key(|c| (c.value(mode), c.power(mode, c.suit)))
+        .expect("there is always a legal card")
+}
+
+fn follow(view: &SeatView, legal_cards: &[Card], mode: GameMode, bottom: Suit) -> Card {
+    let winning = view
+        .table
+        .iter()
+        .max_by_key(|x| x.power)
+        .expect("a card was led");
+    let partner_wins = winning.index == (view.seat + 2) % 4;
+    let last_to_play = view.table.len() == 3;
+    let is_trump = |c: &Card| Some(c.suit) == mode.trump();
+
+    if partner_wins {
+        //Smear points to the partner, keeping trumps
+        return *legal_cards
+            .iter()
+            .max_by_key(|c| (!is_trump(c), c.value(mode), u8::MAX - c.power(mode, bottom)))
+            .expect("there is always a legal card");
+    }
+    let on_table: u32 = view.table.iter().map(|x| x.value as u32).sum();
+    let winners: Vec<&Card> = legal_cards
+        .iter()
+        .filter(|c| c.power(mode, bottom) > winning.power)
+        .collect();
+    //Take the fold as cheaply as possible if it is worth it or nobody can take it back
+    if !winners.is_empty()
+        && (last_to_play || on_table >= 10 || !winners.iter().all(|c| is_trump(c)))
+    {
+        return **winners
+            .iter()
+            .min_by_key(|c| (is_trump(c), c.power(mode, bottom)))
+            .expect("there are winners");
+    }
+    //Losing anyway, discard low
+    *legal_cards
+        .iter()
+        .min_by_key(|c| (is_trump(c), c.value(mode), c.power(mode, bottom)))
+        .expect("there is always a legal card")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, card};
+    use crate::game::Phase;
+    use crate::{Player, TurnInfo};
+
+    fn view<'a>(
+        seat: usize,
+        player: &'a Player,
+        mode: GameMode,
+        table: &'a [TurnInfo],
+    ) -> SeatView<'a> {
+        SeatView {
+            seat,
+            player,
+            mode: Some(mode),
+            table,
+            tricks: &[],
+            points_ac: 0,
+            points_bd: 0,
+        }
+    }
+
+    #[test]
+    fn chooses_trump_with_bour_and_nell() {
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Nine, Suit::Hearts),
+            card(Number::Jack, Suit::Hearts),
+            card(Number::Seven, Suit::Hearts),
+            card(Number::Ace, Suit::Spades),
+            card(Number::Eight, Suit::Diamonds),
+            card(Number::Ten, Suit::Clubs),
+            card(Number::King, Suit::Spades),
+            card(Number::Queen, Suit::Diamonds),
+        ];
+        let player = Player::new(hand, "bot".to_string());
+        let view = view(0, &player, GameMode::Obenabe, &[]);
+        let mut bot = HeuristicBot::new();
+        assert_eq!(bot.schieben(&view), Ok(false));
+        assert_eq!(bot.choose_trump(&view), Ok(GameMode::Trump(Suit::Hearts)));
+    }
+
+    #[test]
+    fn pushes_weak_hands() {
+        let hand = vec![
+            card(Number::Seven, Suit::Clubs),
+            card(Number::Eight, Suit::Clubs),
+            card(Number::Ten, Suit::Diamonds),
+            card(Number::Queen, Suit::Diamonds),
+            card(Number::Eight, Suit::Hearts),
+            card(Number::Ten, Suit::Hearts),
+            card(Number::Queen, Suit::Spades),
+            card(Number::Nine, Suit::Spades),
+            card(Number::Eight, Suit::Spades),
+        ];
+        let player = Player::new(hand, "bot".to_string());
+        assert_eq!(
+            HeuristicBot::new().schieben(&view(0, &player, GameMode::Obenabe, &[])),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn smears_to_partner_and_discards_low_otherwise() {
+        let mode = GameMode::Trump(Suit::Spades);
+        let ten = card(Number::Ten, Suit::Hearts);
+        let six = card(Number::Six, Suit::Hearts);
+        let player = Player::new(vec![six, ten], "bot".to_string());
+        let ace = card(Number::Ace, Suit::Hearts);
+        let seven = card(Number::Seven, Suit::Hearts);
+        //partner (seat 0) leads the Ace, seat 1 plays low
+        let table = [
+            TurnInfo::new(ace, 0, mode, Suit::Hearts),
+            TurnInfo::new(seven, 1, mode, Suit::Hearts),
+        ];
+        let mut bot = HeuristicBot::new();
+        assert_eq!(
+            bot.choose_card(&view(2, &player, mode, &table), &[six, ten]),
+            Ok(ten)
+        );
+        //the opponent leads the Ace
+        let table = [TurnInfo::new(ace, 1, mode, Suit::Hearts)];
+        assert_eq!(
+            bot.choose_card(&view(2, &player, mode, &table), &[six, ten]),
+            Ok(six)
+        );
+    }
+
+    #[test]
+    fn bots_play_a_full_round() {
+        let mut game = fixtures::game(fixtures::shuffled_deal(5), 0);
+        let mut seats = fixtures::seats(|_| HeuristicBot::new());
+        fixtures::play_out(&mut game, &mut seats);
+        assert_eq!(game.phase(), Phase::Finished);
+        assert_eq!(game.tricks().len(), 9);
+    }
+}
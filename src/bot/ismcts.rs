@@ -0,0 +1,263 @@
+use super::tracking::CardTracker;
+use super::{HeuristicBot, DEFAULT_TIME_BUDGET};
+use crate::card::*;
+use crate::card_set::CardSet;
+use crate::controller::{Answer, PlayerController};
+use crate::game::{SeatView, Team};
+use crate::rules::legal_set;
+use crate::solver::score_trick;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 10_000;
+const EXPLORATION: f64 = 0.7;
+
+//Information set Monte Carlo tree search: a single tree over what the seat can know,
+//every iteration going down it with another deal of the unseen cards and finishing the round at random.
+//Trump is chosen like the heuristic bot does
+pub struct IsmctsBot<R: Rng> {
+    rng: R,
+    iterations: usize,
+    time_limit: Duration,
+    heuristic: HeuristicBot,
+}
+
+impl<R: Rng> IsmctsBot<R> {
+    //Stops after the given number of iterations or when the time is up, whichever comes first
+    pub fn new(rng: R, iterations: usize, time_limit: Duration) -> IsmctsBot<R> {
+        IsmctsBot {
+            rng,
+            iterations,
+            time_limit,
+            heuristic: HeuristicBot::new(),
+        }
+    }
+}
+
+impl IsmctsBot<StdRng> {
+    pub fn from_seed(seed: u64) -> IsmctsBot<StdRng> {
+        IsmctsBot::new(
+            StdRng::seed_from_u64(seed),
+            DEFAULT_ITERATIONS,
+            DEFAULT_TIME_BUDGET,
+        )
+    }
+}
+
+impl<R: Rng> PlayerController for IsmctsBot<R> {
+    fn schieben(&mut self, view: &SeatView) -> Answer<bool> {
+        self.heuristic.schieben(view)
+    }
+
+    fn choose_trump(&mut self, view: &SeatView) -> Answer<GameMode> {
+        self.heuristic.choose_trump(view)
+    }
+
+    fn choose_card(&mut self, view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+        if legal_cards.len() == 1 {
+            return Ok(legal_cards[0]);
+        }
+        let mode = view.mode.expect("trump is chosen before playing");
+        let tracker = CardTracker::from_view(view);
+        let start = Instant::now();
+        let mut tree = vec![Node::root()];
+        for _ in 0..self.iterations.max(1) {
+            if start.elapsed() >= self.time_limit {
+                break;
+            }
+            let mut determinization = Determinization {
+                hands: tracker.sample(&mut self.rng),
+                mode,
+                table: view.table.iter().map(|x| (x.index, x.card)).collect(),
+                turn: view.seat,
+                points: [0, 0],
+            };
+            iterate(&mut tree, &mut determinization, &mut self.rng);
+        }
+        //the most visited card is the most trusted one
+        Ok(tree[0]
+            .children
+            .iter()
+            .map(|&child| &tree[child])
+            .filter(|node| legal_cards.contains(&node.card))
+            .max_by_key(|node| node.visits)
+            .map_or(legal_cards[0], |node| node.card))
+    }
+}
+
+struct Node {
+    card: Card,
+    seat: usize, //who played the card, rewards are counted for its team
+    children: Vec<usize>,
+    visits: u32,
+    reward: f64,
+    available: u32, //how many times the card could be chosen
+}
+
+impl Node {
+    fn root() -> Node {
+        Node {
+            card: ALL_CARDS[0],
+            seat: 0,
+            children: Vec::new(),
+            visits: 0,
+            reward: 0.0,
+            available: 0,
+        }
+    }
+
+    fn ucb(&self) -> f64 {
+        self.reward / self.visits as f64
+            + EXPLORATION * ((self.available as f64).ln() / self.visits as f64).sqrt()
+    }
+}
+
+//One determinization of the round, played to its end during an iteration
+struct Determinization {
+    hands: [CardSet; 4],
+    mode: GameMode,
+    table: Vec<(usize, Card)>,
+    turn: usize,
+    points: [u32; 2], //made from the start of the search, by AC and BD
+}
+
+impl Determinization {
+    fn legal_cards(&self) -> Vec<Card> {
+        let table: Vec<Card> = self.table.iter().map(|&(_, c)| c).collect();
+        legal_set(self.hands[self.turn], &table, self.mode).to_vec()
+    }
+
+    fn is_over(&self) -> bool {
+        self.hands.iter().all(|h| h.is_empty())
+    }
+
+    fn play(&mut self, card: Card) {
+        let seat = self.turn;
+        self.hands[seat].remove(card);
+        self.table.push((seat, card));
+        self.turn = (seat + 1) % 4;
+        if self.table.len() == 4 {
+            let (winner, points) = score_trick(&self.table, self.mode, self.is_over());
+            self.points[Team::of(winner) as usize] += points;
+            self.table.clear();
+            self.turn = winner;
+        }
+    }
+
+    //Share of the points made by the team of the seat, between 0 and 1
+    fn reward(&self, seat: usize) -> f64 {
+        let total = self.points[0] + self.points[1];
+        match total {
+            0 => 0.5,
+            _ => self.points[Team::of(seat) as usize] as f64 / total as f64,
+        }
+    }
+}
+
+fn iterate<R: Rng>(tree: &mut Vec<Node>, determinization: &mut Determinization, rng: &mut R) {
+    let mut path = vec![0];
+    let mut node = 0;
+    //selection, among the children this determinization allows
+    while !determinization.is_over() {
+        let legal = determinization.legal_cards();
+        let children: Vec<usize> = tree[node]
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| legal.contains(&tree[child].card))
+            .collect();
+        for &child in &children {
+            tree[child].available += 1;
+        }
+        let untried: Vec<Card> = legal
+            .iter()
+            .copied()
+            .filter(|&c| children.iter().all(|&child| tree[child].card != c))
+            .collect();
+        //expansion
+        if let Some(&card) = untried.choose(rng) {
+            tree.push(Node {
+                card,
+                seat: determinization.turn,
+                children: Vec::new(),
+                visits: 0,
+                reward: 0.0,
+                available: 1,
+            });
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            path.push(child);
+            determinization.play(card);
+            break;
+        }
+        node = *children
+            .iter()
+            .max_by(|&&a, &&b| tree[a].ucb().total_cmp(&tree[b].ucb()))
+            .expect("a legal card is always possible");
+        path.push(node);
+        determinization.play(tree[node].card);
+    }
+    //simulation
+    while !determinization.is_over() {
+        let card = *determinization
+            .legal_cards()
+            .choose(rng)
+            .expect("there is always a legal card");
+        determinization.play(card);
+    }
+    //backpropagation
+    for node in path {
+        let node = &mut tree[node];
+        node.visits += 1;
+        node.reward += determinization.reward(node.seat);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, card};
+    use crate::game::*;
+
+    fn play(seed: u64) -> Vec<Event> {
+        let mut game = fixtures::game(fixtures::shuffled_deal(seed), 0);
+        let limit = Duration::from_secs(60);
+        let mut seats = fixtures::seats(|seat| {
+            IsmctsBot::new(StdRng::seed_from_u64(seed + seat as u64), 100, limit)
+        });
+        let events = fixtures::play_out(&mut game, &mut seats);
+        assert_eq!(game.phase(), Phase::Finished);
+        events
+    }
+
+    #[test]
+    fn plays_a_full_round_the_same_with_the_same_seed() {
+        assert_eq!(play(3), play(3));
+    }
+
+    #[test]
+    fn determinization_scores_the_last_fold() {
+        let mut determinization = Determinization {
+            hands: [
+                CardSet::single(card(Number::Ace, Suit::Hearts)),
+                CardSet::single(card(Number::Six, Suit::Hearts)),
+                CardSet::single(card(Number::Ten, Suit::Clubs)),
+                CardSet::single(card(Number::Six, Suit::Spades)),
+            ],
+            mode: GameMode::Obenabe,
+            table: Vec::new(),
+            turn: 0,
+            points: [0, 0],
+        };
+        while !determinization.is_over() {
+            let card = determinization.legal_cards()[0];
+            determinization.play(card);
+        }
+        //Alice takes her partner's Ten and the 5 of the last fold
+        assert_eq!(determinization.points, [11 + 10 + 5, 0]);
+        assert_eq!(determinization.reward(2), 1.0);
+        assert_eq!(determinization.reward(3), 0.0);
+    }
+}
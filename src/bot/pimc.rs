@@ -0,0 +1,129 @@
+use super::tracking::CardTracker;
+use super::HeuristicBot;
+use crate::card::*;
+use crate::controller::{Answer, PlayerController};
+use crate::game::{SeatView, Team};
+use crate::solver::Position;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_SAMPLES: usize = 20;
+pub const DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(2);
+
+//Perfect information Monte Carlo: deals the unseen cards many times according to what is known,
+//solves every deal as if all hands were open and plays the card with the best average.
+//Trump is chosen like the heuristic bot does
+pub struct PimcBot<R: Rng> {
+    rng: R,
+    samples: usize,
+    time_budget: Duration,
+    heuristic: HeuristicBot,
+}
+
+impl<R: Rng> PimcBot<R> {
+    //Stops after the given number of deals or when the time is up.
+    //If not a single deal could be solved in time, plays like the heuristic bot
+    pub fn new(rng: R, samples: usize, time_budget: Duration) -> PimcBot<R> {
+        PimcBot {
+            rng,
+            samples,
+            time_budget,
+            heuristic: HeuristicBot::new(),
+        }
+    }
+}
+
+impl PimcBot<StdRng> {
+    pub fn from_seed(seed: u64) -> PimcBot<StdRng> {
+        PimcBot::new(
+            StdRng::seed_from_u64(seed),
+            DEFAULT_SAMPLES,
+            DEFAULT_TIME_BUDGET,
+        )
+    }
+}
+
+impl<R: Rng> PlayerController for PimcBot<R> {
+    fn schieben(&mut self, view: &SeatView) -> Answer<bool> {
+        self.heuristic.schieben(view)
+    }
+
+    fn choose_trump(&mut self, view: &SeatView) -> Answer<GameMode> {
+        self.heuristic.choose_trump(view)
+    }
+
+    fn choose_card(&mut self, view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+        if legal_cards.len() == 1 {
+            return Ok(legal_cards[0]);
+        }
+        let mode = view.mode.expect("trump is chosen before playing");
+        let tracker = CardTracker::from_view(view);
+        let deadline = Instant::now() + self.time_budget;
+        let mut totals = vec![0; legal_cards.len()];
+        let mut solved = 0;
+        while solved < self.samples {
+            let mut position = Position::new(
+                tracker.sample(&mut self.rng),
+                mode,
+                view.table.iter().map(|x| (x.index, x.card)).collect(),
+                view.seat,
+            );
+            position.set_deadline(deadline);
+            let remaining: u32 = position
+                .hands
+                .iter()
+                .flatten()
+                .chain(view.table.iter().map(|x| x.card))
+                .map(|c| c.value(mode) as u32)
+                .sum::<u32>()
+                + 5;
+            let points: Option<Vec<u32>> = legal_cards
+                .iter()
+                .map(|&card| position.solve_after(card))
+                .collect();
+            let points = match points {
+                Some(points) => points,
+                None => break,
+            };
+            for (total, points_ac) in totals.iter_mut().zip(points) {
+                *total += match Team::of(view.seat) {
+                    Team::AC => points_ac,
+                    Team::BD => remaining - points_ac,
+                };
+            }
+            solved += 1;
+        }
+        if solved == 0 {
+            //too early in the round to solve a single deal in time
+            return self.heuristic.choose_card(view, legal_cards);
+        }
+        let best = (0..legal_cards.len())
+            .max_by_key(|&i| (totals[i], std::cmp::Reverse(i)))
+            .expect("there is always a legal card");
+        Ok(legal_cards[best])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::game::*;
+
+    #[test]
+    fn plays_legal_cards_to_the_end() {
+        let mut game = fixtures::game(fixtures::shuffled_deal(1), 0);
+        game.apply(Action::ChooseTrump(GameMode::Obenabe)).unwrap();
+        //from the fifth fold on, so that the search stays short
+        while game.tricks().len() < 5 {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        let mut seats = fixtures::seats(|seat| {
+            PimcBot::new(StdRng::seed_from_u64(seat as u64), 5, DEFAULT_TIME_BUDGET)
+        });
+        fixtures::play_out(&mut game, &mut seats);
+        assert_eq!(game.phase(), Phase::Finished);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::card::*;
+use crate::controller::{Answer, PlayerController};
+use crate::game::SeatView;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+//Plays any legal card and chooses any trump, never pushes the choice to its partner
+pub struct RandomBot<R: Rng> {
+    rng: R,
+}
+
+impl<R: Rng> RandomBot<R> {
+    pub fn new(rng: R) -> RandomBot<R> {
+        RandomBot { rng }
+    }
+}
+
+impl RandomBot<StdRng> {
+    pub fn from_seed(seed: u64) -> RandomBot<StdRng> {
+        RandomBot::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> PlayerController for RandomBot<R> {
+    fn schieben(&mut self, _view: &SeatView) -> Answer<bool> {
+        Ok(false)
+    }
+
+    fn choose_trump(&mut self, _view: &SeatView) -> Answer<GameMode> {
+        Ok(*ALL_MODES
+            .choose(&mut self.rng)
+            .expect("there are modes to choose"))
+    }
+
+    fn choose_card(&mut self, _view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+        Ok(*legal_cards
+            .choose(&mut self.rng)
+            .expect("there is always a legal card"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::game::*;
+
+    //every card chosen is legal, or play_out would fail
+    fn play(seed: u64) -> Vec<Event> {
+        let mut game = fixtures::game(fixtures::shuffled_deal(seed), 0);
+        let mut seats = fixtures::seats(|seat| RandomBot::from_seed(seed + seat as u64));
+        fixtures::play_out(&mut game, &mut seats)
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+    }
+}
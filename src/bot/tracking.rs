@@ -0,0 +1,137 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+use crate::game::SeatView;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+//What a seat knows about the cards it cannot see: which are still out,
+//how many each other seat holds and which cards a seat cannot hold since it showed a void
+pub(crate) struct CardTracker {
+    seat: usize,
+    hand: CardSet,
+    unseen: CardSet,
+    counts: [usize; 4],
+    excluded: [CardSet; 4],
+}
+
+impl CardTracker {
+    pub fn from_view(view: &SeatView) -> CardTracker {
+        let mode = view.mode.expect("trump is chosen before playing");
+        let folds = view
+            .tricks
+            .iter()
+            .map(|t| &t.cards[..])
+            .chain(std::iter::once(view.table));
+        let mut played = CardSet::EMPTY;
+        let mut counts = [9; 4];
+        let mut excluded = [CardSet::EMPTY; 4];
+        for fold in folds {
+            let bottom = match fold.first() {
+                Some(first) => first.card.suit,
+                None => continue,
+            };
+            for turn in fold {
+                played.insert(turn.card);
+                counts[turn.index] -= 1;
+                if turn.card.suit == bottom || Some(turn.card.suit) == mode.trump() {
+                    //trumping in says nothing, it is allowed while holding the suit
+                    continue;
+                }
+                let mut void = CardSet::of_suit(bottom);
+                if Some(bottom) == mode.trump() {
+                    //the Bour may be held back when trump is led
+                    void.remove(Card {
+                        suit: bottom,
+                        number: Number::Jack,
+                    });
+                }
+                excluded[turn.index] = excluded[turn.index] | void;
+            }
+        }
+        let hand = view.player.hand();
+        counts[view.seat] = 0;
+        CardTracker {
+            seat: view.seat,
+            hand,
+            unseen: !(hand | played),
+            counts,
+            excluded,
+        }
+    }
+
+    //Cards that may be in the hand of the seat
+    pub fn can_hold(&self, seat: usize, card: Card) -> bool {
+        seat != self.seat && !self.excluded[seat].contains(card)
+    }
+
+    //A deal of the unseen cards to the other seats matching everything known about them.
+    //If the voids cannot be satisfied, falls back to a deal matching only the number of cards
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> [CardSet; 4] {
+        for _ in 0..100 {
+            if let Some(hands) = self.try_sample(rng, true) {
+                return hands;
+            }
+        }
+        self.try_sample(rng, false)
+            .expect("the unseen cards fill the other hands")
+    }
+
+    fn try_sample<R: Rng>(&self, rng: &mut R, use_voids: bool) -> Option<[CardSet; 4]> {
+        let mut cards = self.unseen.to_vec();
+        cards.shuffle(rng);
+        //the most constrained cards first
+        cards.sort_by_key(|&c| (0..4).filter(|&s| self.can_hold(s, c)).count());
+        let mut hands = [CardSet::EMPTY; 4];
+        hands[self.seat] = self.hand;
+        for card in cards {
+            let seats: Vec<usize> = (0..4)
+                .filter(|&s| {
+                    s != self.seat
+                        && hands[s].len() < self.counts[s]
+                        && (!use_voids || self.can_hold(s, card))
+                })
+                .collect();
+            let seat = *seats.choose(rng)?;
+            hands[seat].insert(card);
+        }
+        Some(hands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::game::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn samples_respect_voids() {
+        //every seat holds a single suit: seat 0 Clubs, 1 Diamonds, 2 Hearts, 3 Spades
+        let mut game = fixtures::game(fixtures::sorted_deal(), 0);
+        game.apply(Action::ChooseTrump(GameMode::Obenabe)).unwrap();
+        for _ in 0..5 {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        let view = game.view(1);
+        let tracker = CardTracker::from_view(&view);
+        assert_eq!(tracker.unseen.len(), 36 - 8 - 5);
+        //Charlie and Darlene did not follow Clubs, so Alice holds the Ace
+        assert!(tracker.can_hold(0, ALL_CARDS[8]));
+        assert!(!tracker.can_hold(2, ALL_CARDS[8]));
+        assert!(!tracker.can_hold(3, ALL_CARDS[8]));
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10 {
+            let hands = tracker.sample(&mut rng);
+            assert_eq!(hands[1], view.player.hand());
+            assert_eq!(
+                hands.iter().map(|h| h.len()).collect::<Vec<_>>(),
+                vec![7, 8, 8, 8]
+            );
+            assert!(hands[2].suit(Suit::Clubs).is_empty());
+            assert!(hands[3].suit(Suit::Clubs).is_empty());
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use colored::Colorize;
+use crate::card_set::CardSet;
 use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 pub const ALL_CARDS: [Card; 36] = [
     //Clubs
     Card {
@@ -151,6 +153,21 @@ pub const ALL_CARDS: [Card; 36] = [
     },
 ];
 
+pub const ALL_NUMBERS: [Number; 9] = [
+    Number::Six,
+    Number::Seven,
+    Number::Eight,
+    Number::Nine,
+    Number::Ten,
+    Number::Jack,
+    Number::Queen,
+    Number::King,
+    Number::Ace,
+];
+
+pub const ALL_SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+//Order is the natural one used for sequences in Weis
 #[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Number {
     Six,
@@ -173,6 +190,70 @@ pub enum Suit {
     Spades,
 }
 
+impl Number {
+    pub fn symbol(&self) -> char {
+        match self {
+            Number::Ace => 'A',
+            Number::King => 'K',
+            Number::Queen => 'Q',
+            Number::Jack => 'J',
+            Number::Ten => 'T',
+            Number::Nine => '9',
+            Number::Eight => '8',
+            Number::Seven => '7',
+            Number::Six => '6',
+        }
+    }
+}
+
+impl Suit {
+    //The initial typed at the prompts: P for pique, C for coeur, Q for carreau and F for trèfle
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Spades => 'P',
+            Suit::Hearts => 'C',
+            Suit::Diamonds => 'Q',
+            Suit::Clubs => 'F',
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+            Suit::Clubs => '♣',
+        }
+    }
+}
+
+//What the trump chooser can pick: a trump suit, or no trump with the Aces (Obenabe) or the Sixes (Undenufe) highest
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum GameMode {
+    Trump(Suit),
+    Obenabe,
+    Undenufe,
+}
+
+//Every choice of the trump chooser
+pub const ALL_MODES: [GameMode; 6] = [
+    GameMode::Trump(Suit::Clubs),
+    GameMode::Trump(Suit::Diamonds),
+    GameMode::Trump(Suit::Hearts),
+    GameMode::Trump(Suit::Spades),
+    GameMode::Obenabe,
+    GameMode::Undenufe,
+];
+
+impl GameMode {
+    pub fn trump(&self) -> Option<Suit> {
+        match self {
+            GameMode::Trump(trump) => Some(*trump),
+            _ => None,
+        }
+    }
+}
+
 //Order is derived only for DISPLAY has nothing to do with the power in-game
 #[derive(Eq, Ord, PartialOrd, PartialEq, Debug, Clone, Copy)]
 pub struct Card {
@@ -199,7 +280,23 @@ impl Card {
     //    }
     //}
 
-    pub fn value(&self, trump: Suit) -> u8 {
+    pub fn value(&self, mode: GameMode) -> u8 {
+        let trump = match mode {
+            GameMode::Trump(trump) => trump,
+            //Without trump the Eights are worth 8 and the Ace and Six swap in Undenufe
+            GameMode::Obenabe | GameMode::Undenufe => {
+                return match self.number {
+                    Number::Six if mode == GameMode::Undenufe => 11,
+                    Number::Ace if mode == GameMode::Obenabe => 11,
+                    Number::Eight => 8,
+                    Number::Ten => 10,
+                    Number::Jack => 2,
+                    Number::Queen => 3,
+                    Number::King => 4,
+                    _ => 0,
+                };
+            }
+        };
         match self.number {
             Number::Six => 0,
             Number::Seven => 0,
@@ -225,16 +322,24 @@ impl Card {
         }
     }
 
-    pub fn power(&self, trump: Suit, bottom: Suit) -> u8 {
+    pub fn power(&self, mode: GameMode, bottom: Suit) -> u8 {
+        let trump = match mode {
+            GameMode::Trump(trump) => trump,
+            GameMode::Obenabe | GameMode::Undenufe => {
+                if self.suit != bottom {
+                    return 0;
+                }
+                let rank = self.number as u8 + 1;
+                return match mode {
+                    GameMode::Undenufe => 10 - rank,
+                    _ => rank,
+                };
+            }
+        };
         if self.suit != bottom && self.suit != trump {
             return 0;
         }
-        let trump_bonus: u8;
-        if self.suit == trump {
-            trump_bonus = 9;
-        } else {
-            trump_bonus = 0;
-        }
+        let trump_bonus: u8 = if self.suit == trump { 9 } else { 0 };
         match self.number {
             Number::Six => 1 + trump_bonus,
             Number::Seven => 2 + trump_bonus,
@@ -259,28 +364,351 @@ impl Card {
             Number::Ace => 9 + trump_bonus,
         }
     }
+
     pub fn display(&self) -> String {
-        let s: char = match self.suit {
-            Suit::Spades => '♠',
-            Suit::Hearts => '♥',
-            Suit::Diamonds => '♦',
-            Suit::Clubs => '♣',
+        format!("[ {} ]", self)
+    }
+}
+
+//A Weis, announced during the first fold
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedMeld")
+)]
+pub enum Meld {
+    //From 3 to 9 consecutive cards of the same suit, high is the strongest of them
+    Sequence {
+        suit: Suit,
+        high: Number,
+        length: usize,
+    },
+    FourOfAKind(Number),
+}
+
+//A Meld as it is read, before it is checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+enum UncheckedMeld {
+    Sequence {
+        suit: Suit,
+        high: Number,
+        length: usize,
+    },
+    FourOfAKind(Number),
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<UncheckedMeld> for Meld {
+    type Error = crate::error::Error;
+
+    fn try_from(meld: UncheckedMeld) -> Result<Meld, Self::Error> {
+        let meld = match meld {
+            UncheckedMeld::Sequence { suit, high, length } => Meld::Sequence { suit, high, length },
+            UncheckedMeld::FourOfAKind(number) => Meld::FourOfAKind(number),
         };
-        let n: char = match self.number {
-            Number::Ace => 'A',
-            Number::King => 'K',
-            Number::Queen => 'Q',
-            Number::Jack => 'J',
-            Number::Ten => 'T',
-            Number::Nine => '9',
-            Number::Eight => '8',
-            Number::Seven => '7',
-            Number::Six => '6',
+        if meld.is_possible() {
+            Ok(meld)
+        } else {
+            Err(crate::error::Error::Inconsistent(
+                "no hand holds such a weis",
+            ))
+        }
+    }
+}
+
+impl Meld {
+    //A sequence of 3 to 9 cards ending at high, or four of a kind from the Nines up.
+    //The other methods expect a possible Meld
+    pub fn is_possible(&self) -> bool {
+        match *self {
+            Meld::Sequence { high, length, .. } => {
+                (3..=9).contains(&length) && length <= high as usize + 1
+            }
+            Meld::FourOfAKind(number) => number >= Number::Nine,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            Meld::Sequence { length, .. } => match length {
+                3 => 20,
+                4 => 50,
+                n => 50 * (*n as u32 - 3),
+            },
+            Meld::FourOfAKind(number) => match number {
+                Number::Jack => 200,
+                Number::Nine => 150,
+                _ => 100,
+            },
+        }
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        match *self {
+            Meld::Sequence { suit, high, length } => ALL_NUMBERS
+                [high as usize + 1 - length..=high as usize]
+                .iter()
+                .map(|&number| Card { suit, number })
+                .collect(),
+            Meld::FourOfAKind(number) => ALL_SUITS
+                .iter()
+                .map(|&suit| Card { suit, number })
+                .collect(),
+        }
+    }
+
+    //Higher points win, then more cards, then the higher card (the lower one in Undenufe),
+    //then a sequence in trump. Equal melds are decided by who announced first
+    pub fn cmp_weis(&self, other: &Meld, mode: GameMode) -> Ordering {
+        self.rank(mode).cmp(&other.rank(mode))
+    }
+
+    fn rank(&self, mode: GameMode) -> (u32, usize, u8, bool) {
+        let (length, high, in_trump) = match *self {
+            Meld::Sequence { suit, high, length } => (length, high, mode.trump() == Some(suit)),
+            Meld::FourOfAKind(number) => (4, number, false),
+        };
+        let height = match mode {
+            //the lowest card is what counts
+            GameMode::Undenufe => (ALL_NUMBERS.len() + length - 1 - high as usize) as u8,
+            _ => high as u8,
         };
-        format!("[ {}{} ]", n, s)
+        (self.points(), length, height, in_trump)
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Meld::Sequence { length, .. } => {
+                let name = match length {
+                    3 => "Dreiblatt",
+                    4 => "Vierblatt",
+                    5 => "Fünfblatt",
+                    6 => "Sechsblatt",
+                    7 => "Siebenblatt",
+                    8 => "Achtblatt",
+                    _ => "Neunblatt",
+                };
+                let cards: String = self.cards().iter().map(|c| c.display()).collect();
+                format!("{} {}", name, cards)
+            }
+            Meld::FourOfAKind(_) => {
+                let cards: String = self.cards().iter().map(|c| c.display()).collect();
+                format!("Four of a kind {}", cards)
+            }
+        }
+    }
+}
+
+//Every Weis in the hand. Sequences are as long as possible and four Sixes, Sevens or Eights count for nothing
+pub fn find_melds(hand: CardSet) -> Vec<Meld> {
+    let mut melds = Vec::new();
+    for &suit in ALL_SUITS.iter() {
+        let mut length = 0;
+        for (i, &number) in ALL_NUMBERS.iter().enumerate() {
+            if hand.contains(Card { suit, number }) {
+                length += 1;
+            } else {
+                length = 0;
+            }
+            let run_ends = i == ALL_NUMBERS.len() - 1
+                || !hand.contains(Card {
+                    suit,
+                    number: ALL_NUMBERS[i + 1],
+                });
+            if length >= 3 && run_ends {
+                melds.push(Meld::Sequence {
+                    suit,
+                    high: number,
+                    length,
+                });
+            }
+        }
+    }
+    for &number in ALL_NUMBERS[Number::Nine as usize..].iter() {
+        if hand.iter().filter(|c| c.number == number).count() == 4 {
+            melds.push(Meld::FourOfAKind(number));
+        }
     }
+    melds
 }
 
+//Stöck: the King and the Queen of trump in the same hand
+pub fn has_stoeck(hand: CardSet, trump: Suit) -> bool {
+    hand.contains(Card {
+        suit: trump,
+        number: Number::King,
+    }) && hand.contains(Card {
+        suit: trump,
+        number: Number::Queen,
+    })
+}
+
+//Cards are written `K♥`, or `KC` with the alternate flag `{:#}`
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.alternate() {
+            true => write!(f, "{}", self.letter()),
+            false => write!(f, "{}", self.symbol()),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.alternate() {
+            true => write!(f, "{:#}{:#}", self.number, self.suit),
+            false => write!(f, "{}{}", self.number, self.suit),
+        }
+    }
+}
+
+//The modes are written as their suit, `Obenabe` or `Undenufe`
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameMode::Trump(suit) => suit.fmt(f),
+            GameMode::Obenabe => write!(f, "Obenabe"),
+            GameMode::Undenufe => write!(f, "Undenufe"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    Number(String),
+    Suit(String),
+    Card(String),
+    Mode(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::Number(s) => write!(f, "'{}' is not a card number", s),
+            ParseCardError::Suit(s) => write!(f, "'{}' is not a suit", s),
+            ParseCardError::Card(s) => write!(f, "'{}' is not a card", s),
+            ParseCardError::Mode(s) => write!(f, "'{}' is neither a suit, Obenabe nor Undenufe", s),
+        }
+    }
+}
+
+//Digits and letters as in `KC`, or the names in English, French and German
+impl FromStr for Number {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Number, ParseCardError> {
+        let number = match s.to_lowercase().as_str() {
+            "6" | "six" | "sechs" => Number::Six,
+            "7" | "seven" | "sept" | "sieben" => Number::Seven,
+            "8" | "eight" | "huit" | "acht" => Number::Eight,
+            "9" | "nine" | "neuf" | "neun" => Number::Nine,
+            "t" | "10" | "ten" | "dix" | "zehn" | "banner" => Number::Ten,
+            "j" | "jack" | "valet" | "bube" | "bauer" => Number::Jack,
+            "q" | "queen" | "dame" => Number::Queen,
+            "k" | "king" | "roi" | "könig" | "koenig" => Number::King,
+            "a" | "ace" | "as" | "ass" => Number::Ace,
+            _ => return Err(ParseCardError::Number(s.to_string())),
+        };
+        Ok(number)
+    }
+}
+
+//The symbols, the initials of Suit::letter, the English initials D/H/S (not C, which is coeur)
+//and the English, French and German names
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        let suit = match s.to_lowercase().as_str() {
+            "♣" | "f" | "clubs" | "trèfle" | "trefle" | "kreuz" => Suit::Clubs,
+            "♦" | "q" | "d" | "diamonds" | "carreau" | "karo" | "ecke" => Suit::Diamonds,
+            "♥" | "c" | "h" | "hearts" | "coeur" | "cœur" | "herz" => Suit::Hearts,
+            "♠" | "p" | "s" | "spades" | "pique" | "pik" | "schaufel" => Suit::Spades,
+            _ => return Err(ParseCardError::Suit(s.to_string())),
+        };
+        Ok(suit)
+    }
+}
+
+//Either compact, number first or suit first (`KH`, `K♥`, `10♥`, `♥K`),
+//or in words in any order (`king of hearts`, `roi de coeur`, `Herz König`)
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let words: Vec<&str> = s
+            .split(|c: char| c.is_whitespace() || c == '-')
+            .filter(|w| !w.is_empty() && !["of", "de", "du"].contains(&w.to_lowercase().as_str()))
+            .collect();
+        let pairs: Vec<(&str, &str)> = match words[..] {
+            [a, b] => vec![(a, b)],
+            [word] => word
+                .char_indices()
+                .skip(1)
+                .map(|(i, _)| word.split_at(i))
+                .collect(),
+            _ => Vec::new(),
+        };
+        pairs
+            .iter()
+            .find_map(
+                |&(a, b)| match (a.parse(), b.parse(), b.parse(), a.parse()) {
+                    (Ok(number), Ok(suit), _, _) | (_, _, Ok(number), Ok(suit)) => {
+                        Some(Card { suit, number })
+                    }
+                    _ => None,
+                },
+            )
+            .ok_or_else(|| ParseCardError::Card(s.to_string()))
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<GameMode, ParseCardError> {
+        match s.to_lowercase().as_str() {
+            "obenabe" => Ok(GameMode::Obenabe),
+            "undenufe" => Ok(GameMode::Undenufe),
+            _ => s
+                .parse()
+                .map(GameMode::Trump)
+                .map_err(|_| ParseCardError::Mode(s.to_string())),
+        }
+    }
+}
+
+//With the serde feature, numbers, suits, cards and modes are written in their ASCII form as in
+//"J", "P", "JP" and "Obenabe", and read back with FromStr so that "J♠" is accepted as well
+macro_rules! serde_as_text {
+    ($($t:ty),*) => {$(
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&format_args!("{:#}", self))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                let text = String::deserialize(deserializer)?;
+                text.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+serde_as_text!(Number, Suit, Card, GameMode);
+
 #[cfg(test)]
 mod card_tests {
     use super::*;
@@ -328,7 +756,10 @@ mod card_tests {
         let v = vec![
             card6, card7, card8, card9, card_t, card_j, card_q, card_k, card_a,
         ];
-        let values: Vec<u8> = v.iter().map(|card| card.value(trump)).collect();
+        let values: Vec<u8> = v
+            .iter()
+            .map(|card| card.value(GameMode::Trump(trump)))
+            .collect();
         assert_eq!(values, vec![0, 0, 0, 0, 10, 2, 3, 4, 11]);
     }
     #[test]
@@ -374,7 +805,10 @@ mod card_tests {
         let v = vec![
             card6, card7, card8, card9, card_t, card_j, card_q, card_k, card_a,
         ];
-        let values: Vec<u8> = v.iter().map(|card| card.value(trump)).collect();
+        let values: Vec<u8> = v
+            .iter()
+            .map(|card| card.value(GameMode::Trump(trump)))
+            .collect();
         assert_eq!(values, vec![0, 0, 0, 14, 10, 20, 3, 4, 11]);
     }
 
@@ -422,8 +856,11 @@ mod card_tests {
         let v = vec![
             card6, card7, card8, card9, card_t, card_j, card_q, card_k, card_a,
         ];
-        let values: Vec<u8> = v.iter().map(|card| card.power(trump, bottom)).collect();
-        assert_eq!(values, vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let values: Vec<u8> = v
+            .iter()
+            .map(|card| card.power(GameMode::Trump(trump), bottom))
+            .collect();
+        assert_eq!(values, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
     }
     #[test]
     fn card_correct_power_trump() {
@@ -469,8 +906,154 @@ mod card_tests {
         let v = vec![
             card6, card7, card8, card9, card_t, card_j, card_q, card_k, card_a,
         ];
-        let values: Vec<u8> = v.iter().map(|card| card.power(trump, bottom)).collect();
-        assert_eq!(values, vec![0, 1, 2, 10, 4, 11, 6, 7, 8]);
+        let values: Vec<u8> = v
+            .iter()
+            .map(|card| card.power(GameMode::Trump(trump), bottom))
+            .collect();
+        assert_eq!(values, vec![10, 11, 12, 19, 14, 20, 16, 17, 18]);
+    }
+
+    #[test]
+    fn finds_sequences_and_four_of_a_kind() {
+        let mut hand: Vec<Card> = ALL_CARDS[..5].to_vec(); //6 to Ten of Clubs
+        hand.extend(
+            [Suit::Diamonds, Suit::Hearts, Suit::Spades]
+                .iter()
+                .map(|&suit| Card {
+                    suit,
+                    number: Number::Nine,
+                }),
+        );
+        hand.push(Card {
+            suit: Suit::Hearts,
+            number: Number::Ace,
+        });
+        let melds = find_melds(hand.iter().collect());
+        assert_eq!(
+            melds,
+            vec![
+                Meld::Sequence {
+                    suit: Suit::Clubs,
+                    high: Number::Ten,
+                    length: 5
+                },
+                Meld::FourOfAKind(Number::Nine)
+            ]
+        );
+        assert_eq!(melds[0].points(), 100);
+        assert_eq!(melds[1].points(), 150);
+        assert_eq!(melds[0].cards(), ALL_CARDS[..5].to_vec());
+
+        let sixes: Vec<Card> = ALL_CARDS
+            .iter()
+            .filter(|c| c.number == Number::Six)
+            .copied()
+            .collect();
+        assert!(find_melds(sixes.iter().collect()).is_empty());
+        assert_eq!(
+            find_melds(ALL_CARDS[27..].into()),
+            vec![Meld::Sequence {
+                suit: Suit::Spades,
+                high: Number::Ace,
+                length: 9
+            }]
+        );
+    }
+
+    #[test]
+    fn weis_comparison() {
+        let trump = Suit::Hearts;
+        let four_aces = Meld::FourOfAKind(Number::Ace);
+        let five = Meld::Sequence {
+            suit: Suit::Clubs,
+            high: Number::Ten,
+            length: 5,
+        };
+        let four_high = Meld::Sequence {
+            suit: Suit::Clubs,
+            high: Number::Ace,
+            length: 4,
+        };
+        let four_low = Meld::Sequence {
+            suit: Suit::Spades,
+            high: Number::King,
+            length: 4,
+        };
+        let four_low_trump = Meld::Sequence {
+            suit: trump,
+            high: Number::King,
+            length: 4,
+        };
+        //same points, more cards
+        assert_eq!(
+            five.cmp_weis(&four_aces, GameMode::Trump(trump)),
+            Ordering::Greater
+        );
+        //same length, higher card
+        assert_eq!(
+            four_high.cmp_weis(&four_low_trump, GameMode::Trump(trump)),
+            Ordering::Greater
+        );
+        //same height, trump
+        assert_eq!(
+            four_low_trump.cmp_weis(&four_low, GameMode::Trump(trump)),
+            Ordering::Greater
+        );
+        let four_low_other = Meld::Sequence {
+            suit: Suit::Diamonds,
+            high: Number::King,
+            length: 4,
+        };
+        assert_eq!(
+            four_low.cmp_weis(&four_low_other, GameMode::Trump(trump)),
+            Ordering::Equal
+        );
+        //the lower sequence wins in Undenufe
+        assert_eq!(
+            four_low.cmp_weis(&four_high, GameMode::Undenufe),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn stoeck_needs_king_and_queen_of_trump() {
+        let king = Card {
+            suit: Suit::Hearts,
+            number: Number::King,
+        };
+        let queen = Card {
+            suit: Suit::Hearts,
+            number: Number::Queen,
+        };
+        assert!(has_stoeck([queen, king].iter().collect(), Suit::Hearts));
+        assert!(!has_stoeck([queen, king].iter().collect(), Suit::Spades));
+        assert!(!has_stoeck(CardSet::single(king), Suit::Hearts));
+    }
+
+    #[test]
+    fn no_trump_values_and_power() {
+        let hearts: Vec<Card> = ALL_CARDS[18..27].to_vec();
+        let values: Vec<u8> = hearts.iter().map(|c| c.value(GameMode::Obenabe)).collect();
+        assert_eq!(values, vec![0, 0, 8, 0, 10, 2, 3, 4, 11]);
+        let values: Vec<u8> = hearts.iter().map(|c| c.value(GameMode::Undenufe)).collect();
+        assert_eq!(values, vec![11, 0, 8, 0, 10, 2, 3, 4, 0]);
+        let total: u32 = ALL_CARDS
+            .iter()
+            .map(|c| c.value(GameMode::Undenufe) as u32)
+            .sum();
+        assert_eq!(total, 152);
+
+        let powers: Vec<u8> = hearts
+            .iter()
+            .map(|c| c.power(GameMode::Obenabe, Suit::Hearts))
+            .collect();
+        assert_eq!(powers, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let powers: Vec<u8> = hearts
+            .iter()
+            .map(|c| c.power(GameMode::Undenufe, Suit::Hearts))
+            .collect();
+        assert_eq!(powers, vec![9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(ALL_CARDS[35].power(GameMode::Undenufe, Suit::Hearts), 0);
     }
 
     #[test]
@@ -497,9 +1080,106 @@ mod card_tests {
         };
 
         assert!(big_spade > medium_spade);
-        assert!(!(big_spade < medium_spade));
+        assert!(big_spade >= medium_spade);
         assert!(medium_spade > small_spade);
         assert!(small_spade > other_diamond);
         assert!(other_diamond > other_club);
     }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for card in ALL_CARDS.iter() {
+            assert_eq!(card.to_string().parse(), Ok(*card));
+            assert_eq!(format!("{:#}", card).parse(), Ok(*card));
+            assert_eq!(card.number.to_string().parse(), Ok(card.number));
+            assert_eq!(card.suit.to_string().parse(), Ok(card.suit));
+            assert_eq!(format!("{:#}", card.suit).parse(), Ok(card.suit));
+        }
+        let jack = Card {
+            suit: Suit::Spades,
+            number: Number::Jack,
+        };
+        assert_eq!(jack.to_string(), "J♠");
+        assert_eq!(format!("{:#}", jack), "JP");
+        assert_eq!(jack.display(), "[ J♠ ]");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_as_text() {
+        let card = Card {
+            suit: Suit::Spades,
+            number: Number::Jack,
+        };
+        assert_eq!(serde_json::to_string(&card).unwrap(), r#""JP""#);
+        assert_eq!(serde_json::from_str::<Card>(r#""J♠""#).unwrap(), card);
+        assert_eq!(
+            serde_json::to_string(&[GameMode::Trump(Suit::Hearts), GameMode::Obenabe]).unwrap(),
+            r#"["C","Obenabe"]"#
+        );
+        assert!(serde_json::from_str::<Suit>(r#""X""#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn impossible_melds_are_refused() {
+        let meld = Meld::Sequence {
+            suit: Suit::Clubs,
+            high: Number::Ten,
+            length: 5,
+        };
+        let json = serde_json::to_string(&meld).unwrap();
+        assert_eq!(serde_json::from_str::<Meld>(&json).unwrap(), meld);
+        for json in [
+            r#"{"Sequence":{"suit":"F","high":"6","length":9}}"#,
+            r#"{"Sequence":{"suit":"F","high":"A","length":1}}"#,
+            r#"{"Sequence":{"suit":"F","high":"A","length":10}}"#,
+            r#"{"FourOfAKind":"8"}"#,
+        ]
+        .iter()
+        {
+            assert!(serde_json::from_str::<Meld>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn parse_many_ways() {
+        let king = Card {
+            suit: Suit::Hearts,
+            number: Number::King,
+        };
+        for text in [
+            "KC",
+            "KH",
+            "kh",
+            "K♥",
+            "♥K",
+            "king of hearts",
+            "roi de coeur",
+            "roi cœur",
+            "Herz König",
+            "Herz-König",
+        ]
+        .iter()
+        {
+            assert_eq!(text.parse(), Ok(king), "{}", text);
+        }
+        let ten = Card {
+            suit: Suit::Diamonds,
+            number: Number::Ten,
+        };
+        assert_eq!("10D".parse(), Ok(ten));
+        assert_eq!("TQ".parse(), Ok(ten));
+        assert_eq!("dix de carreau".parse(), Ok(ten));
+        assert_eq!("P".parse(), Ok(Suit::Spades));
+        assert_eq!("Schaufel".parse(), Ok(Suit::Spades));
+        assert_eq!(
+            "KX".parse::<Card>(),
+            Err(ParseCardError::Card("KX".to_string()))
+        );
+        assert_eq!(
+            "X".parse::<Suit>(),
+            Err(ParseCardError::Suit("X".to_string()))
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use crate::card::*;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+//A set of cards as 36 bits, one per card in the order of ALL_CARDS.
+//Iterating gives the cards in that order, which is also the order of a sorted hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const ALL: CardSet = CardSet((1 << 36) - 1);
+
+    pub fn of_suit(suit: Suit) -> CardSet {
+        CardSet(0x1ff << (9 * suit as u64))
+    }
+
+    pub fn single(card: Card) -> CardSet {
+        CardSet(1 << index(card))
+    }
+
+    pub fn from_bits(bits: u64) -> CardSet {
+        CardSet(bits & CardSet::ALL.0)
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & CardSet::single(card).0 != 0
+    }
+
+    //True if the card was not in the set yet
+    pub fn insert(&mut self, card: Card) -> bool {
+        let absent = !self.contains(card);
+        self.0 |= CardSet::single(card).0;
+        absent
+    }
+
+    //True if the card was in the set
+    pub fn remove(&mut self, card: Card) -> bool {
+        let present = self.contains(card);
+        self.0 &= !CardSet::single(card).0;
+        present
+    }
+
+    pub fn union(self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    pub fn suit(self, suit: Suit) -> CardSet {
+        self & CardSet::of_suit(suit)
+    }
+
+    pub fn iter(&self) -> Cards {
+        Cards(self.0)
+    }
+
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+fn index(card: Card) -> u32 {
+    9 * card.suit as u32 + card.number as u32
+}
+
+//The cards of a set, lowest bit first
+pub struct Cards(u64);
+
+impl Iterator for Cards {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let i = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(ALL_CARDS[i as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Cards {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Cards;
+
+    fn into_iter(self) -> Cards {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &CardSet {
+    type Item = Card;
+    type IntoIter = Cards;
+
+    fn into_iter(self) -> Cards {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> CardSet {
+        let mut set = CardSet::EMPTY;
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(iter: I) -> CardSet {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> CardSet {
+        cards.iter().collect()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, other: CardSet) -> CardSet {
+        self.union(other)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, other: CardSet) -> CardSet {
+        self.intersection(other)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, other: CardSet) -> CardSet {
+        self.difference(other)
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    fn not(self) -> CardSet {
+        CardSet::ALL - self
+    }
+}
+
+//A list of cards with the serde feature, as in ["6F", "JP"] for the six of clubs and the
+//jack of spades. Suits are written with their French letters: F clubs (trèfle),
+//Q diamonds (carreau), C hearts (coeur) and P spades (pique)
+#[cfg(feature = "serde")]
+impl serde::Serialize for CardSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CardSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<CardSet, D::Error> {
+        Ok(Vec::<Card>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_follow_all_cards() {
+        for (i, &card) in ALL_CARDS.iter().enumerate() {
+            assert_eq!(CardSet::single(card).bits(), 1 << i);
+        }
+        assert_eq!(CardSet::ALL.to_vec(), ALL_CARDS.to_vec());
+        assert_eq!(CardSet::ALL.len(), 36);
+        assert_eq!((!CardSet::EMPTY), CardSet::ALL);
+    }
+
+    #[test]
+    fn set_operations() {
+        let hand: CardSet = ALL_CARDS[7..12].iter().collect();
+        let hearts = CardSet::of_suit(Suit::Hearts);
+        assert_eq!(hearts.len(), 9);
+        assert!(hearts.iter().all(|c| c.suit == Suit::Hearts));
+        assert_eq!(hand.suit(Suit::Clubs).to_vec(), ALL_CARDS[7..9].to_vec());
+        assert_eq!((hand & CardSet::of_suit(Suit::Diamonds)).len(), 3);
+        assert_eq!((hand - CardSet::of_suit(Suit::Clubs)).len(), 3);
+        assert_eq!((hand | hearts).len(), 14);
+        assert!((hand & hearts).is_empty());
+
+        let mut set = CardSet::EMPTY;
+        assert!(set.insert(ALL_CARDS[3]));
+        assert!(!set.insert(ALL_CARDS[3]));
+        assert!(set.contains(ALL_CARDS[3]));
+        assert!(set.remove(ALL_CARDS[3]));
+        assert!(!set.remove(ALL_CARDS[3]));
+        assert!(set.is_empty());
+    }
+}
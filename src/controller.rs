@@ -0,0 +1,259 @@
+use crate::card::*;
+use crate::display_vec_cards;
+use crate::error::Error;
+use crate::game::*;
+use std::io;
+
+//Decides for one seat. Each seat of a game can be driven by a different kind of controller
+pub trait PlayerController {
+    //Only asked while the trump choice can still be passed to the partner
+    fn schieben(&mut self, view: &SeatView) -> Answer<bool>;
+    fn choose_trump(&mut self, view: &SeatView) -> Answer<GameMode>;
+    //Asked once during the first fold with every Weis in the hand, returns the ones to announce
+    fn choose_weis(&mut self, _view: &SeatView, melds: &[Meld]) -> Answer<Vec<Meld>> {
+        Ok(melds.to_vec())
+    }
+    //legal_cards is never empty and the returned card should be one of them
+    fn choose_card(&mut self, view: &SeatView, legal_cards: &[Card]) -> Answer<Card>;
+    //Called for every event of the round, with the view of this seat after the action
+    fn notify(&mut self, _view: &SeatView, _event: &Event) {}
+}
+
+//What a seat can ask of the game loop instead of deciding. Only the loop holds the record
+//of the round, so no controller ever sees the other hands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    //Write the round, with the standing of its match, to this file, then ask the seat again
+    Save(String),
+    //The team of the seat claims it has reached the target, see Match::declare_out
+    DeclareOut,
+    //Stop playing, as when the input is over
+    Quit,
+    //Stop playing on an error, as when the terminal cannot be read
+    Fail(Error),
+}
+
+pub type Answer<T> = Result<T, Request>;
+
+pub type Seats = [Box<dyn PlayerController>; 4];
+
+//Asks the controller of the current seat for its next action, None when the round is over
+pub fn next_action(game: &GameState, seats: &mut Seats) -> Answer<Option<Action>> {
+    let seat = game.current_seat();
+    let view = game.view(seat);
+    let controller = &mut seats[seat];
+    let action = match game.phase() {
+        Phase::ChoosingTrump { geschoben } => {
+            if !geschoben && controller.schieben(&view)? {
+                Action::Schieben
+            } else {
+                Action::ChooseTrump(controller.choose_trump(&view)?)
+            }
+        }
+        Phase::Playing if game.can_announce_weis() => {
+            Action::AnnounceWeis(controller.choose_weis(&view, &find_melds(view.hand()))?)
+        }
+        Phase::Playing => Action::PlayCard(controller.choose_card(&view, &game.playable_cards())?),
+        Phase::Finished => return Ok(None),
+    };
+    Ok(Some(action))
+}
+
+//Where `save` writes the round when no file is given
+pub const DEFAULT_SAVE_FILE: &str = "jass_save.txt";
+
+//A player sitting at the terminal. Typing `save` or `save <file>` at any prompt writes the round,
+//with the standing of its match if any, to a file to be continued with --resume.
+//Typing `out` in a match declares that the team has reached the target
+#[derive(Default)]
+pub struct Human {
+    pending_trump: Option<GameMode>, //a choice typed in when asked whether to schieben
+}
+
+impl Human {
+    pub fn new() -> Human {
+        Human::default()
+    }
+}
+
+impl PlayerController for Human {
+    fn schieben(&mut self, view: &SeatView) -> Answer<bool> {
+        println!(
+            "It is {}'s turn to choose a trump suit.",
+            view.player.name()
+        );
+        self.pending_trump = ask_for_trump(&view.hand().to_vec(), true)?;
+        Ok(self.pending_trump.is_none())
+    }
+
+    fn choose_trump(&mut self, view: &SeatView) -> Answer<GameMode> {
+        if let Some(trump) = self.pending_trump.take() {
+            return Ok(trump);
+        }
+        println!("Your partner has chibré.");
+        Ok(ask_for_trump(&view.hand().to_vec(), false)?.expect("schieben is not offered"))
+    }
+
+    fn choose_weis(&mut self, view: &SeatView, melds: &[Meld]) -> Answer<Vec<Meld>> {
+        println!("{} can announce:", view.player.name());
+        let mut announced = Vec::new();
+        for meld in melds {
+            println!(
+                "{} ({} points), announce it? (y/n)",
+                meld.display(),
+                meld.points()
+            );
+            loop {
+                match read_answer()?.as_str() {
+                    "y" | "Y" => announced.push(*meld),
+                    "n" | "N" => (),
+                    _ => continue,
+                }
+                break;
+            }
+        }
+        Ok(announced)
+    }
+
+    fn choose_card(&mut self, view: &SeatView, _legal_cards: &[Card]) -> Answer<Card> {
+        if !view.table.is_empty() {
+            print!("On the table: ");
+            display_vec_cards(&view.table.iter().map(|x| x.card).collect::<Vec<Card>>());
+        }
+        let mode = view.mode.expect("trump is chosen before playing");
+        view.player.play_turn(mode)
+    }
+}
+
+//The file of a `save` answer, None for any other answer
+fn save_request(answer: &str) -> Option<&str> {
+    match answer.strip_prefix("save")? {
+        "" => Some(DEFAULT_SAVE_FILE),
+        rest if rest.starts_with(char::is_whitespace) => Some(rest.trim()),
+        _ => None,
+    }
+}
+
+//A trimmed line from the terminal. `save`, `out` and the end of the input are requests for the game loop
+pub(crate) fn read_answer() -> Answer<String> {
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) => return Err(Request::Quit),
+        Ok(_) => (),
+        Err(e) => return Err(Request::Fail(Error::Input(e.to_string()))),
+    }
+    let answer = answer.trim();
+    if answer == "out" {
+        return Err(Request::DeclareOut);
+    }
+    match save_request(answer) {
+        Some(path) => Err(Request::Save(path.to_string())),
+        None => Ok(answer.to_string()),
+    }
+}
+
+//Returns None if the player decided to schieben
+fn ask_for_trump(hand: &[Card], can_schieben: bool) -> Answer<Option<GameMode>> {
+    display_vec_cards(hand);
+    let suits: Vec<String> = ALL_SUITS
+        .iter()
+        .rev()
+        .map(|suit| format!("{:#}: {}", suit, suit))
+        .collect();
+    loop {
+        println!(
+            "Choose a suit ({} | O: Obenabe, U: Undenufe | S: scheiber)",
+            suits.join(", ")
+        );
+        match trump_answer(&read_answer()?) {
+            Some(Action::ChooseTrump(mode)) => return Ok(Some(mode)),
+            Some(Action::Schieben) if can_schieben => return Ok(None),
+            _ => continue,
+        }
+    }
+}
+
+//S pushes the choice to the partner, and suits are read like in the cards, see Suit::letter
+fn trump_answer(answer: &str) -> Option<Action> {
+    match answer.trim().to_lowercase().as_str() {
+        "s" => Some(Action::Schieben),
+        "o" => Some(Action::ChooseTrump(GameMode::Obenabe)),
+        "u" => Some(Action::ChooseTrump(GameMode::Undenufe)),
+        answer => answer.parse().ok().map(Action::ChooseTrump),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    //Always pushes the trump choice and plays the first legal card
+    struct FirstCard {
+        trump: GameMode,
+    }
+
+    impl PlayerController for FirstCard {
+        fn schieben(&mut self, _view: &SeatView) -> Answer<bool> {
+            Ok(true)
+        }
+
+        fn choose_trump(&mut self, _view: &SeatView) -> Answer<GameMode> {
+            Ok(self.trump)
+        }
+
+        fn choose_card(&mut self, _view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+            Ok(legal_cards[0])
+        }
+    }
+
+    #[test]
+    fn save_answers() {
+        assert_eq!(save_request("save"), Some(DEFAULT_SAVE_FILE));
+        assert_eq!(save_request("save my game.txt"), Some("my game.txt"));
+        assert_eq!(save_request("saved"), None);
+        assert_eq!(save_request("s"), None);
+    }
+
+    #[test]
+    fn trump_and_card_prompts_agree_on_suits() {
+        for &suit in ALL_SUITS.iter() {
+            let letter = format!("{:#}", suit);
+            assert_eq!(
+                trump_answer(&letter),
+                Some(Action::ChooseTrump(GameMode::Trump(suit)))
+            );
+            assert_eq!(format!("A{}", letter).parse::<Card>().unwrap().suit, suit);
+        }
+        assert_eq!(
+            trump_answer("c\n"),
+            Some(Action::ChooseTrump(GameMode::Trump(Suit::Hearts)))
+        );
+        assert_eq!(trump_answer("S"), Some(Action::Schieben));
+        assert_eq!(
+            trump_answer("obenabe"),
+            Some(Action::ChooseTrump(GameMode::Obenabe))
+        );
+        assert_eq!(trump_answer("x"), None);
+    }
+
+    #[test]
+    fn scripted_seats_play_a_full_round() {
+        let mut game = fixtures::game(fixtures::sorted_deal(), 1);
+        let trumps = [
+            GameMode::Obenabe,
+            GameMode::Trump(Suit::Diamonds),
+            GameMode::Undenufe,
+            GameMode::Trump(Suit::Spades),
+        ];
+        let mut seats = fixtures::seats(|seat| FirstCard {
+            trump: trumps[seat],
+        });
+        fixtures::play_out(&mut game, &mut seats);
+        //seat 1 pushed to seat 3 who chose its own suit
+        assert_eq!(game.trump(), Some(Suit::Spades));
+        assert_eq!(game.tricks().len(), 9);
+        //every hand is a Neunblatt and the one in trump wins the Weis, seat 3 also has the Stöck
+        assert_eq!(game.points(Team::BD), 157 + 100 + 600 + 20);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+use crate::{distribute_and_create_players, Player};
+use std::fmt;
+use std::str::FromStr;
+
+//The four hands of a round in a short text, as in `6QK♣7♦8TQ♥9A♠ 78J♣...`:
+//for every seat, the numbers of each suit followed by the suit.
+//Numbers and suits are read as single characters by their FromStr, so that suits may also be
+//typed as letters like F, C and P, or D since Q is read as the Queen. Hands are separated by spaces or slashes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deal {
+    hands: [CardSet; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DealError {
+    HandCount(usize),
+    UnknownCharacter(char),
+    MissingSuit,
+    HandSize { seat: usize, size: usize },
+    DuplicateCard(Card),
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DealError::HandCount(n) => write!(f, "a deal has 4 hands, not {}", n),
+            DealError::UnknownCharacter(c) => write!(f, "'{}' is neither a number nor a suit", c),
+            DealError::MissingSuit => write!(f, "numbers must be followed by their suit"),
+            DealError::HandSize { seat, size } => {
+                write!(f, "hand {} has {} cards instead of 9", seat + 1, size)
+            }
+            DealError::DuplicateCard(card) => write!(f, "{} is dealt twice", card),
+        }
+    }
+}
+
+impl Deal {
+    //Each seat gets nine cards in a row, as with distribute_and_create_players
+    pub fn from_deck(deck: &[Card; 36]) -> Deal {
+        let mut hands = [CardSet::EMPTY; 4];
+        for (seat, hand) in hands.iter_mut().enumerate() {
+            *hand = deck[9 * seat..9 * (seat + 1)].iter().collect();
+        }
+        Deal { hands }
+    }
+
+    pub fn from_players(players: &[Player; 4]) -> Deal {
+        Deal {
+            hands: [0, 1, 2, 3].map(|seat| players[seat].hand()),
+        }
+    }
+
+    pub fn hand(&self, seat: usize) -> CardSet {
+        self.hands[seat]
+    }
+
+    //The hands one after the other, so that from_deck gives the same deal back
+    pub fn deck(&self) -> [Card; 36] {
+        let mut deck = ALL_CARDS;
+        for (slot, card) in deck.iter_mut().zip(self.hands.iter().flatten()) {
+            *slot = card;
+        }
+        deck
+    }
+
+    pub fn players(&self, names: [String; 4]) -> [Player; 4] {
+        distribute_and_create_players(self.deck(), names)
+    }
+}
+
+impl fmt::Display for Deal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (seat, hand) in self.hands.iter().enumerate() {
+            if seat > 0 {
+                write!(f, " ")?;
+            }
+            for &suit in ALL_SUITS.iter() {
+                let cards = hand.suit(suit);
+                if !cards.is_empty() {
+                    for card in cards {
+                        write!(f, "{}", card.number)?;
+                    }
+                    write!(f, "{}", suit)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_hand(text: &str) -> Result<Vec<Card>, DealError> {
+    let mut cards = Vec::new();
+    let mut numbers = Vec::new();
+    for c in text.chars() {
+        if let Ok(number) = c.to_string().parse() {
+            numbers.push(number);
+        } else if let Ok(suit) = c.to_string().parse() {
+            cards.extend(numbers.drain(..).map(|number| Card { suit, number }));
+        } else {
+            return Err(DealError::UnknownCharacter(c));
+        }
+    }
+    match numbers.is_empty() {
+        true => Ok(cards),
+        false => Err(DealError::MissingSuit),
+    }
+}
+
+impl FromStr for Deal {
+    type Err = DealError;
+
+    fn from_str(text: &str) -> Result<Deal, DealError> {
+        let texts: Vec<&str> = text
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .filter(|t| !t.is_empty())
+            .collect();
+        if texts.len() != 4 {
+            return Err(DealError::HandCount(texts.len()));
+        }
+        let mut hands = [CardSet::EMPTY; 4];
+        let mut dealt = CardSet::EMPTY;
+        for (seat, text) in texts.iter().enumerate() {
+            let cards = parse_hand(text)?;
+            for &card in cards.iter() {
+                if !dealt.insert(card) {
+                    return Err(DealError::DuplicateCard(card));
+                }
+                hands[seat].insert(card);
+            }
+            if cards.len() != 9 {
+                return Err(DealError::HandSize {
+                    seat,
+                    size: cards.len(),
+                });
+            }
+        }
+        Ok(Deal { hands })
+    }
+}
+
+//Written as its code in JSON
+#[cfg(feature = "serde")]
+impl serde::Serialize for Deal {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Deal {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Deal, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let deal = Deal::from_deck(&ALL_CARDS);
+        let code = deal.to_string();
+        assert_eq!(code, "6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQKA♠");
+        assert_eq!(code.parse(), Ok(deal));
+        assert_eq!(Deal::from_deck(&deal.deck()), deal);
+        assert_eq!(Deal::from_players(&deal.players(Default::default())), deal);
+
+        let mixed = "6QK♣7♦8TQ♥9A♠ 789TJA♣68♦K♥  9TJQKA♦679♥/JAh678TJQKs";
+        let deal: Deal = mixed.parse().unwrap();
+        assert_eq!(deal.hand(1).suit(Suit::Hearts).len(), 1);
+        assert_eq!(deal.to_string().parse(), Ok(deal));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |text: &str| text.parse::<Deal>();
+        assert_eq!(parse("6789TJQKA♣"), Err(DealError::HandCount(1)));
+        assert_eq!(
+            parse("6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQKX"),
+            Err(DealError::UnknownCharacter('X'))
+        );
+        assert_eq!(
+            parse("6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQKA"),
+            Err(DealError::MissingSuit)
+        );
+        assert_eq!(
+            parse("6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQK♠A♣"),
+            Err(DealError::DuplicateCard(Card {
+                suit: Suit::Clubs,
+                number: Number::Ace
+            }))
+        );
+        assert_eq!(
+            parse("6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQK♠"),
+            Err(DealError::HandSize { seat: 3, size: 8 })
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::card::*;
+use crate::deal::DealError;
+use crate::record::RecordError;
+use crate::rules::IllegalReason;
+use std::fmt;
+
+//Every way an action, a player or a notation can be refused by the library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    //The seat tried to act while it was the turn of another one
+    OutOfTurn { seat: usize, turn: usize },
+    CardNotInHand(Card),
+    //1-based, as the positions are shown to the player
+    NoCardAt(usize),
+    IllegalCard(Card, IllegalReason),
+    //Pushing twice, or choosing or pushing once the trump is chosen
+    InvalidTrumpChoice,
+    //Playing or announcing a Weis before the trump is chosen
+    TrumpNotChosen,
+    //A Weis is announced during the first fold only
+    WeisTooLate,
+    WeisAlreadyAnnounced,
+    InvalidWeis(Meld),
+    //The round, or the match, is over
+    GameFinished,
+    //Seats go from 0 to 3
+    NoSuchSeat(usize),
+    //A seat holds more or fewer cards than the fold on the table allows
+    UnevenHands { seat: usize, size: usize },
+    //Four cards are on the table, the fold is already over
+    FoldComplete,
+    //A state that no round can reach, as one read from a file
+    Inconsistent(&'static str),
+    //The terminal could not be read
+    Input(String),
+    ParseCard(ParseCardError),
+    Deal(DealError),
+    Record(RecordError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::OutOfTurn { seat, turn } => write!(
+                f,
+                "seat {} played out of turn, it is the turn of seat {}",
+                seat, turn
+            ),
+            Error::CardNotInHand(c) => write!(f, "the card {} is not in hand", c),
+            Error::NoCardAt(n) => write!(f, "there is no card number {} in hand", n),
+            Error::IllegalCard(c, reason) => {
+                write!(f, "this card {} is not playable: {}", c, reason)
+            }
+            Error::InvalidTrumpChoice => write!(f, "the trump cannot be chosen or passed now"),
+            Error::TrumpNotChosen => write!(f, "the trump has not been chosen yet"),
+            Error::WeisTooLate => write!(f, "a Weis is only announced during the first fold"),
+            Error::WeisAlreadyAnnounced => write!(f, "the Weis was already announced"),
+            Error::InvalidWeis(m) => write!(f, "{} is not in hand", m.display()),
+            Error::GameFinished => write!(f, "the game is already finished"),
+            Error::NoSuchSeat(seat) => write!(f, "there is no seat {}", seat),
+            Error::UnevenHands { seat, size } => write!(
+                f,
+                "seat {} cannot hold {} cards with the others' hands and the table",
+                seat, size
+            ),
+            Error::FoldComplete => write!(f, "the fold on the table is already complete"),
+            Error::Inconsistent(what) => write!(f, "inconsistent game: {}", what),
+            Error::Input(e) => write!(f, "cannot read the answer: {}", e),
+            Error::ParseCard(e) => write!(f, "{}", e),
+            Error::Deal(e) => write!(f, "invalid deal: {}", e),
+            Error::Record(e) => write!(f, "invalid record: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IllegalCard(_, reason) => Some(reason),
+            Error::ParseCard(e) => Some(e),
+            Error::Deal(e) => Some(e),
+            Error::Record(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseCardError> for Error {
+    fn from(e: ParseCardError) -> Error {
+        Error::ParseCard(e)
+    }
+}
+
+impl From<DealError> for Error {
+    fn from(e: DealError) -> Error {
+        Error::Deal(e)
+    }
+}
+
+impl From<RecordError> for Error {
+    fn from(e: RecordError) -> Error {
+        Error::Record(e)
+    }
+}
+
+impl std::error::Error for IllegalReason {}
+impl std::error::Error for ParseCardError {}
+impl std::error::Error for DealError {}
+impl std::error::Error for RecordError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deal::Deal;
+    use std::error::Error as _;
+
+    #[test]
+    fn parse_errors_convert() {
+        fn parse(card: &str, deal: &str) -> Result<(Card, Deal), Error> {
+            Ok((card.parse()?, deal.parse()?))
+        }
+        let error = parse("KX", "").unwrap_err();
+        assert_eq!(
+            error,
+            Error::ParseCard(ParseCardError::Card("KX".to_string()))
+        );
+        assert!(error.source().is_some());
+        assert_eq!(
+            parse("KH", "6789TJQKA♣"),
+            Err(Error::Deal(DealError::HandCount(1)))
+        );
+    }
+}
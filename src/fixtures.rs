@@ -0,0 +1,48 @@
+//Deals and games shared by the tests of every module
+use crate::card::*;
+use crate::controller::{next_action, PlayerController, Seats};
+use crate::deal::Deal;
+use crate::game::{Event, GameState};
+use crate::shuffled_deck;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+pub fn names() -> [String; 4] {
+    crate::default_names()
+}
+
+//Seat 0 holds every Club, 1 every Diamond, 2 every Heart and 3 every Spade
+pub fn sorted_deal() -> Deal {
+    Deal::from_deck(&ALL_CARDS)
+}
+
+pub fn shuffled_deal(seed: u64) -> Deal {
+    Deal::from_deck(&shuffled_deck(&mut StdRng::seed_from_u64(seed)))
+}
+
+pub fn game(deal: Deal, chooser: usize) -> GameState {
+    GameState::new(deal.players(names()), chooser)
+}
+
+pub fn card(number: Number, suit: Suit) -> Card {
+    Card { suit, number }
+}
+
+//The controller of every seat, from its seat number
+pub fn seats<C: PlayerController + 'static>(controller: impl Fn(usize) -> C) -> Seats {
+    [
+        Box::new(controller(0)),
+        Box::new(controller(1)),
+        Box::new(controller(2)),
+        Box::new(controller(3)),
+    ]
+}
+
+//Lets the seats play the round to its end, every action must be accepted
+pub fn play_out(game: &mut GameState, seats: &mut Seats) -> Vec<Event> {
+    let mut events = Vec::new();
+    while let Some(action) = next_action(game, seats).unwrap() {
+        events.extend(game.apply(action).unwrap());
+    }
+    events
+}
@@ -0,0 +1,952 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+#[cfg(feature = "serde")]
+use crate::deal::DealError;
+use crate::error::Error;
+use crate::rules::*;
+use crate::{Player, TurnInfo};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Team {
+    AC,
+    BD,
+}
+
+impl Team {
+    pub fn of(seat: usize) -> Team {
+        match seat % 2 {
+            0 => Team::AC,
+            _ => Team::BD,
+        }
+    }
+
+    pub fn other(&self) -> Team {
+        match self {
+            Team::AC => Team::BD,
+            Team::BD => Team::AC,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    ChoosingTrump { geschoben: bool },
+    Playing,
+    Finished,
+}
+
+//Every action is taken by the player whose turn it currently is
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    ChooseTrump(GameMode),
+    Schieben,
+    //Only during the first fold, before playing a card. Playing without announcing announces nothing
+    AnnounceWeis(Vec<Meld>),
+    PlayCard(Card),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Geschoben {
+        from: usize,
+        to: usize,
+    },
+    TrumpChosen {
+        seat: usize,
+        mode: GameMode,
+    },
+    WeisAnnounced {
+        seat: usize,
+        melds: Vec<Meld>,
+    },
+    CardPlayed {
+        seat: usize,
+        card: Card,
+    },
+    //Given when the second card of the Stöck is played
+    Stoeck {
+        seat: usize,
+    },
+    //Only the team with the best Weis scores, for all the Weis of both its players
+    WeisScored {
+        team: Team,
+        points: u32,
+    },
+    TrickWon {
+        seat: usize,
+        cards: Vec<Card>,
+        points: u32,
+    },
+    FullMatch {
+        team: Team,
+        points: u32,
+    },
+    RoundOver {
+        summary: RoundSummary,
+    },
+    //Only emitted by a Match
+    NewRound {
+        chooser: usize,
+    },
+    DeclaredOut {
+        team: Team,
+    },
+    MatchOver {
+        winner: Team,
+        points_ac: u32,
+        points_bd: u32,
+    },
+}
+
+pub type Events = Vec<Event>;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trick {
+    pub cards: Vec<TurnInfo>,
+    pub winner: usize,
+    pub points: u32,
+}
+
+//Who took which fold, in the order they were played
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundSummary {
+    pub mode: Option<GameMode>,
+    pub trick_winners: Vec<usize>,
+    pub trick_points: Vec<u32>, //card points before any multiplier, cinq de der included
+    pub points_ac: u32,
+    pub points_bd: u32,
+}
+
+impl RoundSummary {
+    pub fn tricks_won(&self, team: Team) -> usize {
+        self.trick_winners
+            .iter()
+            .filter(|&&seat| Team::of(seat) == team)
+            .count()
+    }
+
+    //The team that took all nine folds, if any
+    pub fn full_match(&self) -> Option<Team> {
+        [Team::AC, Team::BD]
+            .iter()
+            .copied()
+            .find(|&team| self.tricks_won(team) == 9)
+    }
+}
+
+//Every point of a round except the Stöck is multiplied depending on the trump chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Multipliers {
+    pub clubs: u32,
+    pub diamonds: u32,
+    pub hearts: u32,
+    pub spades: u32,
+    pub obenabe: u32,
+    pub undenufe: u32,
+}
+
+impl Multipliers {
+    pub fn single() -> Multipliers {
+        Multipliers {
+            clubs: 1,
+            diamonds: 1,
+            hearts: 1,
+            spades: 1,
+            obenabe: 1,
+            undenufe: 1,
+        }
+    }
+
+    //Acorns and Bells (Clubs and Diamonds) count single, Roses and Shields (Hearts and Spades)
+    //double, Obenabe and Undenufe triple
+    pub fn by_suit() -> Multipliers {
+        Multipliers {
+            clubs: 1,
+            diamonds: 1,
+            hearts: 2,
+            spades: 2,
+            obenabe: 3,
+            undenufe: 3,
+        }
+    }
+
+    pub fn of(&self, mode: GameMode) -> u32 {
+        match mode {
+            GameMode::Trump(Suit::Clubs) => self.clubs,
+            GameMode::Trump(Suit::Diamonds) => self.diamonds,
+            GameMode::Trump(Suit::Hearts) => self.hearts,
+            GameMode::Trump(Suit::Spades) => self.spades,
+            GameMode::Obenabe => self.obenabe,
+            GameMode::Undenufe => self.undenufe,
+        }
+    }
+}
+
+impl Default for Multipliers {
+    fn default() -> Multipliers {
+        Multipliers::single()
+    }
+}
+
+//What a single seat is allowed to see of the round
+pub struct SeatView<'a> {
+    pub seat: usize,
+    pub player: &'a Player,
+    pub mode: Option<GameMode>,
+    pub table: &'a [TurnInfo],
+    pub tricks: &'a [Trick],
+    pub points_ac: u32,
+    pub points_bd: u32,
+}
+
+impl<'a> SeatView<'a> {
+    pub fn hand(&self) -> CardSet {
+        self.player.hand()
+    }
+
+    pub fn bottom(&self) -> Option<Suit> {
+        self.table.first().map(|x| x.card.suit)
+    }
+}
+
+//A single round of Schieber, driven only through apply().
+//With the serde feature a state is checked as it is read, see GameState::check
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedGameState")
+)]
+pub struct GameState {
+    players: [Player; 4],
+    phase: Phase,
+    chooser: usize, //the player who had the right to choose trump also begins the first fold
+    turn: usize,
+    mode: Option<GameMode>,
+    table: Vec<TurnInfo>,
+    tricks: Vec<Trick>,
+    weis: [Option<Vec<Meld>>; 4], //None until the player announced or played its first card
+    stoeck: Option<usize>,        //the seat holding the King and Queen of trump
+    multipliers: Multipliers,
+    points_ac: u32,
+    points_bd: u32,
+}
+
+//The fields of a GameState as they are read, before they are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedGameState {
+    players: [Player; 4],
+    phase: Phase,
+    chooser: usize,
+    turn: usize,
+    mode: Option<GameMode>,
+    table: Vec<TurnInfo>,
+    tricks: Vec<Trick>,
+    weis: [Option<Vec<Meld>>; 4],
+    stoeck: Option<usize>,
+    multipliers: Multipliers,
+    points_ac: u32,
+    points_bd: u32,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<UncheckedGameState> for GameState {
+    type Error = Error;
+
+    fn try_from(state: UncheckedGameState) -> Result<GameState, Error> {
+        let mut game = GameState {
+            players: state.players,
+            phase: state.phase,
+            chooser: state.chooser,
+            turn: state.turn,
+            mode: state.mode,
+            table: state.table,
+            tricks: state.tricks,
+            weis: state.weis,
+            stoeck: state.stoeck,
+            multipliers: state.multipliers,
+            points_ac: state.points_ac,
+            points_bd: state.points_bd,
+        };
+        game.check()?;
+        if game.phase == Phase::Playing {
+            game.update_playable_cards();
+        }
+        Ok(game)
+    }
+}
+
+impl GameState {
+    pub fn new(players: [Player; 4], chooser: usize) -> GameState {
+        GameState::new_with_multipliers(players, chooser, Multipliers::default())
+    }
+
+    pub fn new_with_multipliers(
+        players: [Player; 4],
+        chooser: usize,
+        multipliers: Multipliers,
+    ) -> GameState {
+        GameState {
+            players,
+            phase: Phase::ChoosingTrump { geschoben: false },
+            chooser,
+            turn: chooser,
+            mode: None,
+            table: Vec::new(),
+            tricks: Vec::new(),
+            weis: [None, None, None, None],
+            stoeck: None,
+            multipliers,
+            points_ac: 0,
+            points_bd: 0,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn current_seat(&self) -> usize {
+        self.turn
+    }
+
+    //The seat who had the right to choose the trump of this round
+    pub fn chooser(&self) -> usize {
+        self.chooser
+    }
+
+    pub fn player(&self, seat: usize) -> &Player {
+        &self.players[seat]
+    }
+
+    pub fn view(&self, seat: usize) -> SeatView<'_> {
+        SeatView {
+            seat,
+            player: &self.players[seat],
+            mode: self.mode,
+            table: &self.table,
+            tricks: &self.tricks,
+            points_ac: self.points_ac,
+            points_bd: self.points_bd,
+        }
+    }
+
+    pub fn mode(&self) -> Option<GameMode> {
+        self.mode
+    }
+
+    //The trump suit, None before the choice or when playing without trump
+    pub fn trump(&self) -> Option<Suit> {
+        self.mode.and_then(|m| m.trump())
+    }
+
+    pub fn bottom(&self) -> Option<Suit> {
+        self.table.first().map(|x| x.card.suit)
+    }
+
+    pub fn table(&self) -> &[TurnInfo] {
+        &self.table
+    }
+
+    pub fn tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    pub fn points(&self, team: Team) -> u32 {
+        match team {
+            Team::AC => self.points_ac,
+            Team::BD => self.points_bd,
+        }
+    }
+
+    pub fn summary(&self) -> RoundSummary {
+        RoundSummary {
+            mode: self.mode,
+            trick_winners: self.tricks.iter().map(|t| t.winner).collect(),
+            trick_points: self.tricks.iter().map(|t| t.points).collect(),
+            points_ac: self.points_ac,
+            points_bd: self.points_bd,
+        }
+    }
+
+    pub fn announced_weis(&self, seat: usize) -> &[Meld] {
+        self.weis[seat].as_deref().unwrap_or(&[])
+    }
+
+    //True when the current player could still announce a Weis it holds
+    pub fn can_announce_weis(&self) -> bool {
+        self.phase == Phase::Playing
+            && self.tricks.is_empty()
+            && self.weis[self.turn].is_none()
+            && !find_melds(self.players[self.turn].hand()).is_empty()
+    }
+
+    //Cards the current player is allowed to play, empty outside of the playing phase
+    pub fn playable_cards(&self) -> Vec<Card> {
+        match self.phase {
+            Phase::Playing => self.players[self.turn].playable_cards.to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<Events, Error> {
+        match (self.phase, action) {
+            (Phase::ChoosingTrump { geschoben }, Action::Schieben) => {
+                if geschoben {
+                    return Err(Error::InvalidTrumpChoice);
+                }
+                let from = self.turn;
+                self.turn = (from + 2) % 4;
+                self.phase = Phase::ChoosingTrump { geschoben: true };
+                Ok(vec![Event::Geschoben {
+                    from,
+                    to: self.turn,
+                }])
+            }
+            (Phase::ChoosingTrump { .. }, Action::ChooseTrump(mode)) => {
+                let seat = self.turn;
+                self.mode = Some(mode);
+                self.stoeck = mode
+                    .trump()
+                    .and_then(|trump| (0..4).find(|&x| has_stoeck(self.players[x].hand(), trump)));
+                self.turn = self.chooser;
+                self.phase = Phase::Playing;
+                self.update_playable_cards();
+                Ok(vec![Event::TrumpChosen { seat, mode }])
+            }
+            (Phase::Playing, Action::AnnounceWeis(melds)) => self.announce_weis(melds),
+            (Phase::Playing, Action::PlayCard(card)) => self.play_card(card),
+            (Phase::Playing, _) => Err(Error::InvalidTrumpChoice),
+            (Phase::ChoosingTrump { .. }, _) => Err(Error::TrumpNotChosen),
+            (Phase::Finished, _) => Err(Error::GameFinished),
+        }
+    }
+
+    //As apply, for integrators that let every seat send its actions at any time
+    pub fn apply_as(&mut self, seat: usize, action: Action) -> Result<Events, Error> {
+        match self.phase {
+            Phase::Finished => Err(Error::GameFinished),
+            _ if seat != self.turn => Err(Error::OutOfTurn {
+                seat,
+                turn: self.turn,
+            }),
+            _ => self.apply(action),
+        }
+    }
+
+    //Refuses a state that apply() could not have reached, as one read from a file:
+    //seats outside of 0..4, a card held or played twice, or hands not fitting the folds played
+    #[cfg(feature = "serde")]
+    fn check(&self) -> Result<(), Error> {
+        let seats = self.tricks.iter().map(|t| t.winner).chain(
+            self.table
+                .iter()
+                .chain(self.tricks.iter().flat_map(|t| t.cards.iter()))
+                .map(|x| x.index),
+        );
+        for seat in [self.chooser, self.turn]
+            .iter()
+            .copied()
+            .chain(self.stoeck)
+            .chain(seats)
+        {
+            if seat >= 4 {
+                return Err(Error::NoSuchSeat(seat));
+            }
+        }
+        if self.table.len() >= 4 {
+            return Err(Error::FoldComplete);
+        }
+        if self.tricks.len() > 9 || self.tricks.iter().any(|t| t.cards.len() != 4) {
+            return Err(Error::Inconsistent(
+                "a fold has four cards and a round nine folds",
+            ));
+        }
+        let choosing = matches!(self.phase, Phase::ChoosingTrump { .. });
+        let played = !self.table.is_empty() || !self.tricks.is_empty();
+        if choosing == self.mode.is_some() || (choosing && played) {
+            return Err(Error::Inconsistent(
+                "cards are played once the trump is chosen",
+            ));
+        }
+        if (self.phase == Phase::Finished) != (self.tricks.len() == 9) {
+            return Err(Error::Inconsistent("the round is over after nine folds"));
+        }
+        let mut seen = CardSet::EMPTY;
+        let played = self
+            .table
+            .iter()
+            .chain(self.tricks.iter().flat_map(|t| t.cards.iter()))
+            .map(|x| x.card);
+        for card in self
+            .players
+            .iter()
+            .flat_map(|p| p.hand.iter())
+            .chain(played)
+        {
+            if !seen.insert(card) {
+                return Err(Error::Deal(DealError::DuplicateCard(card)));
+            }
+        }
+        for (seat, player) in self.players.iter().enumerate() {
+            let on_table = self.table.iter().any(|x| x.index == seat) as usize;
+            if player.hand.len() + on_table + self.tricks.len() != 9 {
+                return Err(Error::UnevenHands {
+                    seat,
+                    size: player.hand.len(),
+                });
+            }
+        }
+        match self.table.first() {
+            Some(first) if (first.index + self.table.len()) % 4 != self.turn => {
+                Err(Error::Inconsistent("the turn does not follow the table"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn update_playable_cards(&mut self) {
+        let mode = self.mode.expect("trump is chosen before playing");
+        let played_cards: Vec<Card> = self.table.iter().map(|x| x.card).collect();
+        self.players[self.turn].update_playable_cards(&played_cards, mode);
+    }
+
+    fn announce_weis(&mut self, melds: Vec<Meld>) -> Result<Events, Error> {
+        let seat = self.turn;
+        if !self.tricks.is_empty() {
+            return Err(Error::WeisTooLate);
+        }
+        if self.weis[seat].is_some() {
+            return Err(Error::WeisAlreadyAnnounced);
+        }
+        let in_hand = find_melds(self.players[seat].hand());
+        for (i, meld) in melds.iter().enumerate() {
+            if !in_hand.contains(meld) || melds[..i].contains(meld) {
+                return Err(Error::InvalidWeis(*meld));
+            }
+        }
+        self.weis[seat] = Some(melds.clone());
+        Ok(vec![Event::WeisAnnounced { seat, melds }])
+    }
+
+    fn play_card(&mut self, card: Card) -> Result<Events, Error> {
+        let seat = self.turn;
+        if !self.players[seat].hand.contains(card) {
+            return Err(Error::CardNotInHand(card));
+        }
+        if !self.players[seat].playable_cards.contains(card) {
+            let table: Vec<Card> = self.table.iter().map(|x| x.card).collect();
+            let mode = self.mode.expect("trump is chosen before playing");
+            let reason = check_card(&self.players[seat].hand().to_vec(), &table, mode, card)
+                .expect_err("playable cards are the legal ones");
+            return Err(Error::IllegalCard(card, reason));
+        }
+        self.players[seat]
+            .discard(&card)
+            .expect("card was checked to be in hand");
+        if self.weis[seat].is_none() {
+            self.weis[seat] = Some(Vec::new());
+        }
+        let mode = self.mode.expect("trump is chosen before playing");
+        let bottom = self.bottom().unwrap_or(card.suit);
+        self.table.push(TurnInfo::new(card, seat, mode, bottom));
+        let mut events = vec![Event::CardPlayed { seat, card }];
+        let stoeck_card = Some(card.suit) == mode.trump()
+            && (card.number == Number::King || card.number == Number::Queen);
+        if self.stoeck == Some(seat)
+            && stoeck_card
+            && !has_stoeck_card(self.players[seat].hand, card.suit)
+        {
+            self.add_points(Team::of(seat), 20);
+            events.push(Event::Stoeck { seat });
+        }
+
+        if self.table.len() < 4 {
+            self.turn = (seat + 1) % 4;
+        } else {
+            self.finish_trick(&mut events);
+        }
+        if self.phase == Phase::Playing {
+            self.update_playable_cards();
+        }
+        Ok(events)
+    }
+
+    fn finish_trick(&mut self, events: &mut Events) {
+        if self.tricks.is_empty() {
+            self.score_weis(events);
+        }
+        let cards = std::mem::take(&mut self.table);
+        let winner = cards
+            .iter()
+            .max_by_key(|x| x.power)
+            .expect("a trick has four cards")
+            .index;
+        let mut points = cards.iter().map(|x| x.value as u32).sum::<u32>();
+        //cinq de der
+        let last = self.players[0].hand.is_empty();
+        if last {
+            points += 5;
+        }
+        let scored = points * self.multiplier();
+        self.add_points(Team::of(winner), scored);
+        events.push(Event::TrickWon {
+            seat: winner,
+            cards: cards.iter().map(|x| x.card).collect(),
+            points: scored,
+        });
+        self.tricks.push(Trick {
+            cards,
+            winner,
+            points,
+        });
+        self.turn = winner; //the winner begins the next fold
+
+        if last {
+            //full match bonus
+            if let Some(team) = self.summary().full_match() {
+                let points = 100 * self.multiplier();
+                self.add_points(team, points);
+                events.push(Event::FullMatch { team, points });
+            }
+            self.phase = Phase::Finished;
+            events.push(Event::RoundOver {
+                summary: self.summary(),
+            });
+        }
+    }
+
+    fn score_weis(&mut self, events: &mut Events) {
+        let mode = self.mode.expect("trump is chosen before playing");
+        //on equal Weis the one announced first wins, so look in the order of the first fold
+        let mut best: Option<(usize, Meld)> = None;
+        for seat in (0..4).map(|x| (x + self.chooser) % 4) {
+            for meld in self.announced_weis(seat) {
+                let better = match best {
+                    None => true,
+                    Some((_, b)) => meld.cmp_weis(&b, mode) == Ordering::Greater,
+                };
+                if better {
+                    best = Some((seat, *meld));
+                }
+            }
+        }
+        if let Some((seat, _)) = best {
+            let team = Team::of(seat);
+            let points = (0..4)
+                .filter(|&x| Team::of(x) == team)
+                .flat_map(|x| self.announced_weis(x))
+                .map(|m| m.points())
+                .sum::<u32>()
+                * self.multiplier();
+            self.add_points(team, points);
+            events.push(Event::WeisScored { team, points });
+        }
+    }
+
+    fn add_points(&mut self, team: Team, points: u32) {
+        match team {
+            Team::AC => self.points_ac += points,
+            Team::BD => self.points_bd += points,
+        }
+    }
+
+    fn multiplier(&self) -> u32 {
+        self.multipliers
+            .of(self.mode.expect("trump is chosen before playing"))
+    }
+}
+
+fn has_stoeck_card(hand: CardSet, trump: Suit) -> bool {
+    hand.iter()
+        .any(|c| c.suit == trump && (c.number == Number::King || c.number == Number::Queen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn new_game() -> GameState {
+        fixtures::game(fixtures::sorted_deal(), 0)
+    }
+
+    #[test]
+    fn schieben_passes_to_partner_once() {
+        let mut game = new_game();
+        let events = game.apply(Action::Schieben).unwrap();
+        assert_eq!(events, vec![Event::Geschoben { from: 0, to: 2 }]);
+        assert_eq!(game.current_seat(), 2);
+        assert_eq!(game.apply(Action::Schieben), Err(Error::InvalidTrumpChoice));
+
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Hearts)))
+            .unwrap();
+        //the player who pushed still begins the first fold
+        assert_eq!(game.current_seat(), 0);
+        assert_eq!(game.phase(), Phase::Playing);
+    }
+
+    #[test]
+    fn rejects_invalid_cards() {
+        let mut game = new_game();
+        let card = game.player(1).hand().iter().next().unwrap();
+        assert_eq!(
+            game.apply(Action::PlayCard(card)),
+            Err(Error::TrumpNotChosen)
+        );
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Spades)))
+            .unwrap();
+        assert_eq!(
+            game.apply(Action::ChooseTrump(GameMode::Obenabe)),
+            Err(Error::InvalidTrumpChoice)
+        );
+        assert_eq!(
+            game.apply_as(1, Action::PlayCard(card)),
+            Err(Error::OutOfTurn { seat: 1, turn: 0 })
+        );
+        assert_eq!(
+            game.apply(Action::PlayCard(card)),
+            Err(Error::CardNotInHand(card))
+        );
+    }
+
+    #[test]
+    fn only_best_weis_scores() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Hearts)))
+            .unwrap();
+        let mut seen = Vec::new();
+        while game.tricks().is_empty() {
+            //every hand holds a Neunblatt, the Hearts one is in trump
+            if game.can_announce_weis() {
+                let melds = find_melds(game.player(game.current_seat()).hand());
+                game.apply(Action::AnnounceWeis(melds)).unwrap();
+            }
+            let card = game.playable_cards()[0];
+            seen.extend(game.apply(Action::PlayCard(card)).unwrap());
+        }
+        assert!(seen.contains(&Event::WeisScored {
+            team: Team::AC,
+            points: 600
+        }));
+        assert!(!seen
+            .iter()
+            .any(|e| matches!(e, Event::WeisScored { team: Team::BD, .. })));
+        assert_eq!(
+            game.apply(Action::AnnounceWeis(Vec::new())),
+            Err(Error::WeisTooLate)
+        );
+    }
+
+    #[test]
+    fn illegal_card_comes_with_a_reason() {
+        let ace = Card {
+            suit: Suit::Clubs,
+            number: Number::Ace,
+        };
+        let six = Card {
+            suit: Suit::Clubs,
+            number: Number::Six,
+        };
+        let heart = Card {
+            suit: Suit::Hearts,
+            number: Number::Six,
+        };
+        let players = [
+            Player::new(vec![ace], "Alice".to_string()),
+            Player::new(vec![six, heart], "Bob".to_string()),
+            Player::new(Vec::new(), "Charlie".to_string()),
+            Player::new(Vec::new(), "Darlene".to_string()),
+        ];
+        let mut game = GameState::new(players, 0);
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Spades)))
+            .unwrap();
+        game.apply(Action::PlayCard(ace)).unwrap();
+        let error = game.apply(Action::PlayCard(heart)).unwrap_err();
+        assert_eq!(
+            error,
+            Error::IllegalCard(heart, IllegalReason::MustFollowSuit(Suit::Clubs))
+        );
+        assert!(error.to_string().contains("you must follow the suit"));
+    }
+
+    #[test]
+    fn weis_must_be_in_hand() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Spades)))
+            .unwrap();
+        let four_jacks = Meld::FourOfAKind(Number::Jack);
+        assert_eq!(
+            game.apply(Action::AnnounceWeis(vec![four_jacks])),
+            Err(Error::InvalidWeis(four_jacks))
+        );
+        game.apply(Action::AnnounceWeis(Vec::new())).unwrap();
+        assert_eq!(
+            game.apply(Action::AnnounceWeis(Vec::new())),
+            Err(Error::WeisAlreadyAnnounced)
+        );
+    }
+
+    #[test]
+    fn stoeck_on_second_card() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Clubs)))
+            .unwrap();
+        let mut stoeck_after = Vec::new();
+        while game.phase() == Phase::Playing {
+            let card = game.playable_cards()[0];
+            let events = game.apply(Action::PlayCard(card)).unwrap();
+            if events.contains(&Event::Stoeck { seat: 0 }) {
+                stoeck_after.push(card);
+            }
+        }
+        //the Queen is played first, the King after it
+        assert_eq!(
+            stoeck_after,
+            vec![Card {
+                suit: Suit::Clubs,
+                number: Number::King
+            }]
+        );
+    }
+
+    #[test]
+    fn multiplied_points() {
+        let players = fixtures::sorted_deal().players(fixtures::names());
+        let mut game = GameState::new_with_multipliers(players, 0, Multipliers::by_suit());
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Hearts)))
+            .unwrap();
+        let mut events = Vec::new();
+        while game.phase() == Phase::Playing {
+            let card = game.playable_cards()[0];
+            events.extend(game.apply(Action::PlayCard(card)).unwrap());
+        }
+        //Charlie holds every Heart and takes every fold, the Stöck is not doubled
+        assert!(events.contains(&Event::FullMatch {
+            team: Team::AC,
+            points: 200
+        }));
+        assert_eq!(game.points(Team::AC), (157 + 100) * 2 + 20);
+        assert_eq!(game.tricks().iter().map(|t| t.points).sum::<u32>(), 157);
+    }
+
+    #[test]
+    fn full_match_needs_every_fold() {
+        let mut summary = RoundSummary {
+            mode: Some(GameMode::Obenabe),
+            trick_winners: vec![0, 2, 2, 0, 0, 2, 0, 2, 0],
+            trick_points: vec![0; 9],
+            points_ac: 0,
+            points_bd: 0,
+        };
+        assert_eq!(summary.full_match(), Some(Team::AC));
+        //taking the last fold is not enough
+        summary.trick_winners[3] = 1;
+        assert_eq!(summary.tricks_won(Team::AC), 8);
+        assert_eq!(summary.full_match(), None);
+        summary.trick_winners.pop();
+        assert_eq!(summary.full_match(), None);
+    }
+
+    #[test]
+    fn no_match_bonus_when_folds_are_shared() {
+        //deal every fourth card of the sorted deck to the same player so that suits are mixed
+        let mut deck = ALL_CARDS;
+        for (i, card) in deck.iter_mut().enumerate() {
+            *card = ALL_CARDS[(i % 9) * 4 + i / 9];
+        }
+        let mut game = fixtures::game(crate::deal::Deal::from_deck(&deck), 0);
+        game.apply(Action::ChooseTrump(GameMode::Obenabe)).unwrap();
+        while game.phase() == Phase::Playing {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        let summary = game.summary();
+        assert_eq!(summary.trick_winners.len(), 9);
+        assert!(summary.tricks_won(Team::AC) > 0);
+        assert!(summary.tricks_won(Team::BD) > 0);
+        assert_eq!(summary.full_match(), None);
+        assert_eq!(summary.trick_points.iter().sum::<u32>(), 157);
+        assert_eq!(summary.points_ac + summary.points_bd, 157);
+    }
+
+    #[test]
+    fn full_round_counts_all_points() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Clubs)))
+            .unwrap();
+        while game.phase() == Phase::Playing {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        assert_eq!(game.tricks().len(), 9);
+        //every hand is one full suit so the first player wins everything, Stöck included
+        assert_eq!(game.points(Team::AC), 157 + 100 + 20);
+        assert_eq!(game.points(Team::BD), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn state_survives_json() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Trump(Suit::Hearts)))
+            .unwrap();
+        for _ in 0..6 {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        let json = serde_json::to_string(&game).unwrap();
+        assert!(json.contains(r#""mode":"C""#));
+        let mut copy: GameState = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&copy).unwrap(), json);
+        while game.phase() == Phase::Playing {
+            let card = game.playable_cards()[0];
+            assert_eq!(
+                copy.apply(Action::PlayCard(card)),
+                game.apply(Action::PlayCard(card))
+            );
+        }
+        assert_eq!(copy.summary(), game.summary());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn refuses_impossible_states() {
+        let mut game = new_game();
+        game.apply(Action::ChooseTrump(GameMode::Obenabe)).unwrap();
+        for _ in 0..6 {
+            let card = game.playable_cards()[0];
+            game.apply(Action::PlayCard(card)).unwrap();
+        }
+        let json = serde_json::to_string(&game).unwrap();
+        let turn = format!(r#""turn":{}"#, game.current_seat());
+        let error = serde_json::from_str::<GameState>(&json.replace(&turn, r#""turn":7"#));
+        assert!(error
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("there is no seat 7"));
+
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["players"][3]["hand"].as_array_mut().unwrap().pop();
+        let error = serde_json::from_value::<GameState>(value);
+        assert!(error
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("seat 3 cannot hold 7 cards"));
+
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["table"][0]["card"] = value["players"][0]["hand"][0].clone();
+        assert!(serde_json::from_value::<GameState>(value).is_err());
+    }
+}
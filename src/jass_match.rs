@@ -0,0 +1,457 @@
+use crate::deal::Deal;
+use crate::error::Error;
+use crate::game::*;
+use crate::record::{Record, RecordError, Standing};
+use crate::shuffled_deck;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+
+//Usual targets, but any positive number works
+pub const TARGETS: [u32; 3] = [1000, 1500, 2500];
+
+//Repeated rounds of Schieber until a team reaches the target
+pub struct Match {
+    names: [String; 4],
+    target: u32,
+    multipliers: Multipliers,
+    chooser: usize,
+    seed: u64,
+    rng: StdRng,     //gives the seed of every round
+    round_seed: u64, //the deal of the current round is shuffled from it
+    deal: Deal,
+    game: GameState,
+    records: Vec<Record>, //one per round, the last one being played
+    rounds: usize,        //rounds over before the current one
+    //points of finished rounds
+    points_ac: u32,
+    points_bd: u32,
+    //points made so far in the current round, they are written down when the round is over
+    round_ac: u32,
+    round_bd: u32,
+    first_to_target: Option<Team>,
+    winner: Option<Team>,
+}
+
+impl Match {
+    pub fn new(names: [String; 4], target: u32) -> Match {
+        Match::new_with_multipliers(names, target, Multipliers::default())
+    }
+
+    pub fn new_with_multipliers(
+        names: [String; 4],
+        target: u32,
+        multipliers: Multipliers,
+    ) -> Match {
+        Match::new_with_rng(names, target, multipliers, &mut thread_rng())
+    }
+
+    //The seed of the match is drawn from the given generator
+    pub fn new_with_rng<R: RngCore>(
+        names: [String; 4],
+        target: u32,
+        multipliers: Multipliers,
+        rng: &mut R,
+    ) -> Match {
+        Match::new_seeded(names, target, multipliers, rng.next_u64())
+    }
+
+    //The same seed always deals the same rounds
+    pub fn new_seeded(
+        names: [String; 4],
+        target: u32,
+        multipliers: Multipliers,
+        seed: u64,
+    ) -> Match {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let round_seed = rng.gen();
+        let (deal, game) = deal(&names, 0, multipliers, round_seed);
+        let mut jass_match = Match {
+            names,
+            target,
+            multipliers,
+            chooser: 0,
+            seed,
+            rng,
+            round_seed,
+            deal,
+            game,
+            records: Vec::new(),
+            rounds: 0,
+            points_ac: 0,
+            points_bd: 0,
+            round_ac: 0,
+            round_bd: 0,
+            first_to_target: None,
+            winner: None,
+        };
+        jass_match.records.push(jass_match.round_record());
+        jass_match
+    }
+
+    //Continues the match of a round record, as saved from Match::record, at the point the record ends
+    pub fn resume(record: &Record) -> Result<Match, Error> {
+        let standing = record.standing.ok_or(RecordError::Missing("match"))?;
+        record.replay()?;
+        let mut jass_match = Match::new_seeded(
+            record.names.clone(),
+            standing.target,
+            record.multipliers,
+            standing.seed,
+        );
+        for _ in 0..standing.rounds {
+            jass_match.next_round();
+        }
+        if jass_match.deal != record.deal || jass_match.chooser != record.chooser {
+            return Err(RecordError::NotInMatch.into());
+        }
+        jass_match.points_ac = standing.points_ac;
+        jass_match.points_bd = standing.points_bd;
+        jass_match.records = vec![jass_match.round_record()];
+        for (seat, action) in record.actions() {
+            jass_match.apply_as(seat, action)?;
+        }
+        Ok(jass_match)
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    //Dealing again with this seed gives the current round, see crate::play_round
+    pub fn round_seed(&self) -> u64 {
+        self.round_seed
+    }
+
+    //The hands as they were dealt at the start of the current round
+    pub fn deal(&self) -> Deal {
+        self.deal
+    }
+
+    //The current round so far, it plays again with Record::replay
+    pub fn record(&self) -> &Record {
+        self.records.last().expect("a match has a round")
+    }
+
+    //Every round since the match was started or resumed, in order
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    pub fn winner(&self) -> Option<Team> {
+        self.winner
+    }
+
+    //Points of the finished rounds only
+    pub fn points(&self, team: Team) -> u32 {
+        match team {
+            Team::AC => self.points_ac,
+            Team::BD => self.points_bd,
+        }
+    }
+
+    //Points of the finished rounds plus what the team made in the current round
+    pub fn running_points(&self, team: Team) -> u32 {
+        match team {
+            Team::AC => self.points_ac + self.round_ac,
+            Team::BD => self.points_bd + self.round_bd,
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<Events, Error> {
+        if self.winner.is_some() {
+            return Err(Error::GameFinished);
+        }
+        let mut events = self.game.apply(action)?;
+        let record = self.records.last_mut().expect("a match has a round");
+        for event in events.iter() {
+            record.push(event);
+        }
+        let mut i = 0;
+        while i < events.len() {
+            match events[i] {
+                Event::Stoeck { seat } => self.add_points(Team::of(seat), 20),
+                Event::WeisScored { team, points } => self.add_points(team, points),
+                Event::TrickWon { seat, points, .. } => self.add_points(Team::of(seat), points),
+                Event::FullMatch { team, points } => self.add_points(team, points),
+                Event::RoundOver { .. } => {
+                    self.points_ac += std::mem::take(&mut self.round_ac);
+                    self.points_bd += std::mem::take(&mut self.round_bd);
+                    match self.first_to_target {
+                        Some(winner) => self.finish(winner, &mut events),
+                        None => {
+                            self.next_round();
+                            events.push(Event::NewRound {
+                                chooser: self.chooser,
+                            });
+                        }
+                    }
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        Ok(events)
+    }
+
+    //As apply, refusing the actions of a seat whose turn it is not
+    pub fn apply_as(&mut self, seat: usize, action: Action) -> Result<Events, Error> {
+        let turn = self.game.current_seat();
+        if self.winner.is_none() && seat != turn {
+            return Err(Error::OutOfTurn { seat, turn });
+        }
+        self.apply(action)
+    }
+
+    //A team may claim mid-round that it has reached the target. It wins if it is right and loses otherwise
+    pub fn declare_out(&mut self, team: Team) -> Result<Events, Error> {
+        if self.winner.is_some() {
+            return Err(Error::GameFinished);
+        }
+        let winner = match self.running_points(team) >= self.target {
+            true => team,
+            false => team.other(),
+        };
+        let mut events = vec![Event::DeclaredOut { team }];
+        self.points_ac += std::mem::take(&mut self.round_ac);
+        self.points_bd += std::mem::take(&mut self.round_bd);
+        self.finish(winner, &mut events);
+        Ok(events)
+    }
+
+    fn next_round(&mut self) {
+        self.chooser = (self.chooser + 1) % 4;
+        self.round_seed = self.rng.gen();
+        let (deal, game) = deal(&self.names, self.chooser, self.multipliers, self.round_seed);
+        self.deal = deal;
+        self.game = game;
+        self.rounds += 1;
+        self.records.push(self.round_record());
+    }
+
+    fn round_record(&self) -> Record {
+        let mut record = Record::new(
+            self.names.clone(),
+            self.deal,
+            self.chooser,
+            self.multipliers,
+        );
+        record.seed = Some(self.round_seed);
+        record.standing = Some(Standing {
+            seed: self.seed,
+            target: self.target,
+            rounds: self.rounds,
+            points_ac: self.points_ac,
+            points_bd: self.points_bd,
+        });
+        record
+    }
+
+    fn add_points(&mut self, team: Team, points: u32) {
+        match team {
+            Team::AC => self.round_ac += points,
+            Team::BD => self.round_bd += points,
+        }
+        if self.first_to_target.is_none() && self.running_points(team) >= self.target {
+            self.first_to_target = Some(team);
+        }
+    }
+
+    fn finish(&mut self, winner: Team, events: &mut Events) {
+        self.winner = Some(winner);
+        events.push(Event::MatchOver {
+            winner,
+            points_ac: self.points_ac,
+            points_bd: self.points_bd,
+        });
+    }
+}
+
+fn deal(
+    names: &[String; 4],
+    chooser: usize,
+    multipliers: Multipliers,
+    seed: u64,
+) -> (Deal, GameState) {
+    let deal = Deal::from_deck(&shuffled_deck(&mut StdRng::seed_from_u64(seed)));
+    let game = GameState::new_with_multipliers(deal.players(names.clone()), chooser, multipliers);
+    (deal, game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::*;
+    use crate::fixtures::{self, names};
+
+    fn next_action(game: &GameState) -> Action {
+        match game.phase() {
+            Phase::ChoosingTrump { .. } => Action::ChooseTrump(GameMode::Trump(Suit::Hearts)),
+            _ => Action::PlayCard(game.playable_cards()[0]),
+        }
+    }
+
+    #[test]
+    fn chooser_rotates_until_target() {
+        let mut jass_match = Match::new_seeded(names(), 1000, Multipliers::default(), 11);
+        let mut choosers = vec![jass_match.game().current_seat()];
+        while jass_match.winner().is_none() {
+            let action = next_action(jass_match.game());
+            for event in jass_match.apply(action).unwrap() {
+                if let Event::NewRound { chooser } = event {
+                    choosers.push(chooser);
+                }
+            }
+        }
+        for (i, chooser) in choosers.iter().enumerate() {
+            assert_eq!(*chooser, i % 4);
+        }
+        let winner = jass_match.winner().unwrap();
+        assert!(jass_match.points(winner) >= 1000);
+        assert_eq!(jass_match.apply(Action::Schieben), Err(Error::GameFinished));
+        assert_eq!(
+            jass_match.apply_as(3, Action::Schieben),
+            Err(Error::GameFinished)
+        );
+    }
+
+    #[test]
+    fn first_team_to_reach_target_wins() {
+        let mut jass_match = Match::new_seeded(names(), 1000, Multipliers::default(), 12);
+        jass_match.points_ac = 999;
+        jass_match.points_bd = 999;
+        let mut first = None;
+        while jass_match.winner().is_none() {
+            let action = next_action(jass_match.game());
+            for event in jass_match.apply(action).unwrap() {
+                //whoever is given points first goes over first
+                let scorer = match event {
+                    Event::Stoeck { seat } => Some(Team::of(seat)),
+                    Event::WeisScored { team, points } if points > 0 => Some(team),
+                    Event::TrickWon { seat, points, .. } if points > 0 => Some(Team::of(seat)),
+                    _ => None,
+                };
+                first = first.or(scorer);
+            }
+        }
+        assert_eq!(jass_match.winner(), first);
+    }
+
+    #[test]
+    fn stoeck_makes_the_difference() {
+        let mut jass_match = Match::new(names(), 1000);
+        //Alice holds every Club so she takes every fold and has the Stöck
+        jass_match.game = fixtures::game(fixtures::sorted_deal(), 0);
+        jass_match.points_ac = 1000 - (157 + 100 + 20);
+        jass_match
+            .apply(Action::ChooseTrump(GameMode::Trump(Suit::Clubs)))
+            .unwrap();
+        while jass_match.winner().is_none() {
+            let card = jass_match.game().playable_cards()[0];
+            jass_match.apply(Action::PlayCard(card)).unwrap();
+        }
+        assert_eq!(jass_match.winner(), Some(Team::AC));
+        assert_eq!(jass_match.points(Team::AC), 1000);
+    }
+
+    #[test]
+    fn declaring_out() {
+        let mut jass_match = Match::new(names(), 1000);
+        jass_match.points_bd = 1000;
+        jass_match.declare_out(Team::AC).unwrap();
+        assert_eq!(jass_match.winner(), Some(Team::BD));
+
+        let mut jass_match = Match::new(names(), 1000);
+        jass_match.points_ac = 950;
+        jass_match.round_ac = 50;
+        let events = jass_match.declare_out(Team::AC).unwrap();
+        assert_eq!(jass_match.winner(), Some(Team::AC));
+        assert_eq!(
+            events.last(),
+            Some(&Event::MatchOver {
+                winner: Team::AC,
+                points_ac: 1000,
+                points_bd: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn same_seed_same_rounds() {
+        let play = |seed| {
+            let mut jass_match = Match::new_seeded(names(), 1000, Multipliers::default(), seed);
+            let mut deals = Vec::new();
+            while deals.len() < 3 {
+                let hand = jass_match.game().player(0).hand();
+                assert_eq!(jass_match.deal().hand(0), hand);
+                deals.push((jass_match.round_seed(), hand));
+                while jass_match.game().phase() != Phase::Finished {
+                    let action = next_action(jass_match.game());
+                    if let Some(Event::NewRound { .. }) = jass_match.apply(action).unwrap().last() {
+                        break;
+                    }
+                }
+            }
+            deals
+        };
+        assert_eq!(play(42), play(42));
+        assert_ne!(play(42), play(43));
+        //a round is dealt from its own seed alone
+        let (seed, hand) = play(42)[1];
+        let (_, game) = deal(&names(), 1, Multipliers::default(), seed);
+        assert_eq!(game.player(0).hand(), hand);
+    }
+
+    #[test]
+    fn records_every_round() {
+        let mut jass_match = Match::new_seeded(names(), 1000, Multipliers::default(), 5);
+        while jass_match.records().len() < 2 {
+            let action = next_action(jass_match.game());
+            jass_match.apply(action).unwrap();
+        }
+        let first = jass_match.records()[0].replay().unwrap();
+        assert_eq!(first.phase(), Phase::Finished);
+        assert_eq!(first.points(Team::AC), jass_match.points(Team::AC));
+        assert_eq!(first.points(Team::BD), jass_match.points(Team::BD));
+        assert_eq!(jass_match.record().seed, Some(jass_match.round_seed()));
+        assert_eq!(jass_match.record().deal, jass_match.deal());
+        assert_eq!(jass_match.record().chooser, 1);
+        assert!(jass_match.record().folds.is_empty());
+    }
+
+    #[test]
+    fn resumes_from_the_record_of_a_round() {
+        let mut jass_match = Match::new_seeded(names(), 1000, Multipliers::default(), 9);
+        while jass_match.records().len() < 3 || jass_match.game().tricks().len() < 4 {
+            let action = next_action(jass_match.game());
+            jass_match.apply(action).unwrap();
+        }
+        let saved: Record = jass_match.record().to_string().parse().unwrap();
+        assert_eq!(saved.standing.unwrap().rounds, 2);
+        let mut resumed = Match::resume(&saved).unwrap();
+        assert_eq!(resumed.deal(), jass_match.deal());
+        for team in [Team::AC, Team::BD].iter() {
+            assert_eq!(resumed.points(*team), jass_match.points(*team));
+            assert_eq!(
+                resumed.running_points(*team),
+                jass_match.running_points(*team)
+            );
+        }
+        //the next rounds are dealt the same as well
+        while jass_match.records().len() < 5 {
+            let action = next_action(jass_match.game());
+            assert_eq!(resumed.apply(action.clone()), jass_match.apply(action));
+        }
+        assert_eq!(resumed.record(), jass_match.record());
+
+        let mut alone = saved;
+        alone.standing = None;
+        assert_eq!(
+            Match::resume(&alone).err(),
+            Some(Error::Record(RecordError::Missing("match")))
+        );
+    }
+}
@@ -1,58 +1,73 @@
 use colored::Colorize;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::{io, ops::RangeInclusive};
-mod card;
+use rand::{RngCore, SeedableRng};
+use std::fs;
+pub mod bot;
+pub mod card;
+pub mod card_set;
+pub mod controller;
+pub mod deal;
+pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod game;
+pub mod jass_match;
+pub mod record;
+pub mod rules;
+pub mod solver;
+pub mod viewer;
 use card::*;
+use card_set::CardSet;
+use controller::*;
+use deal::Deal;
+use error::Error;
+use game::*;
+use jass_match::*;
+use record::Record;
+use rules::*;
 
 #[derive(Clone)]
-struct Player {
-    hand: Vec<Card>,
-    playable_cards: Vec<Card>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Player {
+    hand: CardSet,
+    playable_cards: CardSet,
     name: String,
-    //TODO implement cacher so that we don't have to recalculate playable_cards() every time
 }
 
-#[allow(dead_code)]
 impl Player {
-    fn new_with_empty_hand(name: String) -> Player {
+    fn new(hand: Vec<Card>, name: String) -> Player {
         Player {
-            hand: Vec::new(),
-            playable_cards: Vec::new(),
+            hand: hand.into_iter().collect(),
             name,
+            playable_cards: CardSet::EMPTY,
         }
     }
 
-    fn new(hand: Vec<Card>, name: String) -> Player {
-        Player {
-            hand,
-            name,
-            playable_cards: Vec::new(),
-        }
+    //The hand as a set, iterating it yields the cards in order
+    pub fn hand(&self) -> CardSet {
+        self.hand
     }
 
-    fn discard(&mut self, card: &Card) -> Result<Card, &str> {
-        if let Ok(i) = self.hand.binary_search(card) {
-            Ok(self.hand.remove(i))
-        } else {
-            Err("Card not found")
-        }
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    fn discard_index(&mut self, i: usize) -> Result<Card, &str> {
-        if i >= self.hand.len() {
-            Err("Error: attempting to discard out of bounds")
+    fn discard(&mut self, card: &Card) -> Result<Card, Error> {
+        if self.hand.remove(*card) {
+            Ok(*card)
         } else {
-            Ok(self.hand.remove(i))
+            Err(Error::CardNotInHand(*card))
         }
     }
 
-    fn display_hand(&self, trump: Suit, bottom: Option<Suit>) {
+    fn display_hand(&self, mode: GameMode) {
         println!("{}'s hand:", self.name);
+        let trump = mode.trump();
         for card in self.hand.iter() {
-            if card.suit == trump && card.number == Number::Jack {
+            if Some(card.suit) == trump && card.number == Number::Jack {
                 print!("{}", card.display().white());
-            } else if card.suit == trump && self.playable_cards.contains(card) {
+            } else if Some(card.suit) == trump && self.playable_cards.contains(card) {
                 print!("{}", card.display().green());
             } else if self.playable_cards.contains(card) {
                 print!("{}", card.display().blue());
@@ -60,132 +75,44 @@ impl Player {
                 print!("{}", card.display().red());
             }
         }
-        print!("\n");
+        println!();
     }
 
-    fn update_playable_cards(
-        &mut self,
-        played_cards: &Vec<Card>,
-        trump: Suit,
-        bottom: Option<Suit>,
-    ) {
-        let whole_hand = self.hand.clone();
-        let mut trumps_on_table = played_cards
-            .clone()
-            .into_iter()
-            .filter(|x| x.suit == trump)
-            .collect::<Vec<Card>>();
-        match bottom {
-            None => self.playable_cards = whole_hand,
-            Some(b) => {
-                println!("Asking for suit {:?}", b);
-                //If the requested suit is trump and you have one you must play one
-                if b == trump {
-                    if has_suit(&self.hand, trump) {
-                        self.playable_cards =
-                            whole_hand.into_iter().filter(|x| x.suit == trump).collect();
-                    } else {
-                        self.playable_cards = whole_hand;
-                    }
-                //If the requested suit is not trump...
-                } else {
-                    trumps_on_table.sort_by_key(|a| a.power(trump, b));
-                    let highest_trump_played_power = match trumps_on_table.first() {
-                        None => 0,
-                        Some(t) => t.power(trump, b),
-                    };
-                    dbg!(highest_trump_played_power);
-                    //If you have the requested suit you may choose
-                    //Play a trump higher than the strongest one on the table
-                    //Or follow the suit
-                    if has_suit(&self.hand, b) {
-                        self.playable_cards = whole_hand
-                            .into_iter()
-                            .filter(|x| {
-                                x.suit == b
-                                    || (x.suit == trump
-                                        && x.power(trump, b) > highest_trump_played_power)
-                            })
-                            .collect();
-                    //If you don't have a suit you may play anything
-                    //Except a trump lower that the strongest one on the table
-                    } else {
-                        self.playable_cards = whole_hand
-                            .into_iter()
-                            .filter(|x| {
-                                x.suit != trump
-                                    || (x.suit == trump
-                                        && x.power(trump, b) > highest_trump_played_power)
-                            })
-                            .collect();
-                    }
-                }
-            }
-        }
-        //In any case you are never forced to play Bour
-        let bour_copy = Card {
-            suit: trump,
-            number: Number::Jack,
-        };
-        if self.playable_cards.len() == 1 && self.playable_cards.contains(&bour_copy) {
-            self.playable_cards = self.hand.clone() //whole_hand
-        }
-        //print!("{}'s playable cards are:", self.name);
-        //display_vec_cards(&self.playable_cards);
+    fn update_playable_cards(&mut self, played_cards: &[Card], mode: GameMode) {
+        self.playable_cards = legal_set(self.hand, played_cards, mode);
     }
 
-    fn play_turn(
-        &mut self,
-        played_cards: &Vec<Card>,
-        trump: Suit,
-        bottom: &mut Option<Suit>,
-    ) -> Card {
-        self.update_playable_cards(played_cards, trump, *bottom);
+    //Only reads the choice, the rules are checked by the GameState
+    fn play_turn(&self, mode: GameMode) -> Answer<Card> {
         loop {
-            self.display_hand(trump, *bottom);
-            println!("Please select a card (1-{}):", self.hand.len());
-            let stdin = io::stdin();
-            let mut i = String::new();
-            stdin.read_line(&mut i).expect("Failed to read line");
-            let i: usize = match i.trim().parse() {
-                Ok(0) => continue,
-                Ok(n) => n - 1,
-                Err(_) => continue,
-            };
-            let selected_card = match self.hand.get(i) {
-                None => {
-                    println!("This index, {} is out of bounds", i);
-                    continue;
-                }
-                Some(c) => c,
-            };
-            if !self.playable_cards.contains(selected_card) {
-                println!("this card {} is not playable", selected_card.display());
-                continue;
-            } else if let None = bottom {
-                *bottom = Some(selected_card.suit);
-            }
-            match self.discard_index(i) {
-                Ok(card) => return card,
-                Err(s) => {
-                    println!("{}", s);
-                    continue;
-                }
+            self.display_hand(mode);
+            println!(
+                "Please select a card, by name like KC or by position (1-{}):",
+                self.hand.len()
+            );
+            match self.select_card(&read_answer()?) {
+                Ok(card) => return Ok(card),
+                Err(e) => println!("{}", e),
             }
         }
     }
-}
 
-fn has_suit(hand: &Vec<Card>, search_suit: Suit) -> bool {
-    for card in hand {
-        if card.suit == search_suit {
-            return true;
+    //A card of the hand from its name, or from its 1-based position in the hand
+    fn select_card(&self, answer: &str) -> Result<Card, Error> {
+        if let Ok(n) = answer.parse::<usize>() {
+            return n
+                .checked_sub(1)
+                .and_then(|i| self.hand.iter().nth(i))
+                .ok_or(Error::NoCardAt(n));
+        }
+        match answer.parse::<Card>()? {
+            card if self.hand.contains(card) => Ok(card),
+            card => Err(Error::CardNotInHand(card)),
         }
     }
-    false
 }
 
-fn display_vec_cards(cards: &Vec<Card>) {
+pub(crate) fn display_vec_cards(cards: &[Card]) {
     for card in cards {
         match card.suit {
             Suit::Spades => print!("{}", card.display().blue()),
@@ -194,20 +121,27 @@ fn display_vec_cards(cards: &Vec<Card>) {
             Suit::Clubs => print!("{}", card.display().purple()),
         }
     }
-    print!("\n");
+    println!();
+}
+
+//The same generator state always gives the same deck
+pub fn shuffled_deck<R: RngCore>(rng: &mut R) -> [Card; 36] {
+    let mut deck = ALL_CARDS;
+    deck.shuffle(rng);
+    deck
 }
 
-fn distribute_and_create_players(deck: [Card; 36], names: [String; 4]) -> [Player; 4] {
+pub fn distribute_and_create_players(deck: [Card; 36], names: [String; 4]) -> [Player; 4] {
     let mut hand1 = Vec::new();
     let mut hand2 = Vec::new();
     let mut hand3 = Vec::new();
     let mut hand4 = Vec::new();
-    for i in 0..=35 {
+    for (i, card) in deck.iter().enumerate() {
         match i / 9 {
-            0 => hand1.push(deck[i].clone()),
-            1 => hand2.push(deck[i].clone()),
-            2 => hand3.push(deck[i].clone()),
-            3 => hand4.push(deck[i].clone()),
+            0 => hand1.push(*card),
+            1 => hand2.push(*card),
+            2 => hand3.push(*card),
+            3 => hand4.push(*card),
             _ => unreachable!(),
         };
     }
@@ -225,17 +159,18 @@ fn distribute_and_create_players(deck: [Card; 36], names: [String; 4]) -> [Playe
 }
 
 #[derive(Debug, Clone)]
-struct TurnInfo {
-    card: Card, //in the future we want players to be able to see the last fold played. That's why this is here
-    power: u8,
-    value: u8,
-    index: usize,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurnInfo {
+    pub card: Card, //in the future we want players to be able to see the last fold played. That's why this is here
+    pub power: u8,
+    pub value: u8,
+    pub index: usize,
 }
 
 impl TurnInfo {
-    fn new(card: Card, index: usize, trump: Suit, bottom: Suit) -> TurnInfo {
-        let power = card.power(trump, bottom);
-        let value = card.value(trump);
+    fn new(card: Card, index: usize, mode: GameMode, bottom: Suit) -> TurnInfo {
+        let power = card.power(mode, bottom);
+        let value = card.value(mode);
         TurnInfo {
             card,
             index,
@@ -245,135 +180,225 @@ impl TurnInfo {
     }
 }
 
-fn ask_for_trump(players: &[Player], index: usize, was_geschorben: bool) -> Suit {
-    match was_geschorben {
-        false => println!(
-            "It is {}'s turn to choose a trump suit.",
-            players[index].name
+fn display_event(game: &GameState, event: &Event) {
+    match event {
+        Event::TrickWon {
+            seat,
+            cards,
+            points,
+        } => {
+            print!("the played cards were...");
+            display_vec_cards(cards);
+            println!(
+                "the fold goes to {} for {} points",
+                game.player(*seat).name,
+                points
+            );
+        }
+        Event::WeisAnnounced { seat, melds } => {
+            for meld in melds {
+                println!("{} announces {}", game.player(*seat).name, meld.display());
+            }
+        }
+        Event::TrumpChosen { seat, mode } => {
+            println!("{} chooses {}", game.player(*seat).name, mode)
+        }
+        Event::Stoeck { seat } => println!("{}: Stöck! (20 points)", game.player(*seat).name),
+        Event::WeisScored { team, points } => {
+            println!("Team {:?} scores {} points of Weis", team, points)
+        }
+        Event::FullMatch { team, points } => {
+            println!("Full match from team {:?} ({} points)", team, points)
+        }
+        Event::RoundOver { summary } => {
+            println!(
+                "round over -- points_ac: {}  points_bd: {}",
+                summary.points_ac, summary.points_bd
+            );
+            println!(
+                "folds taken -- team AC: {}  team BD: {}",
+                summary.tricks_won(Team::AC),
+                summary.tricks_won(Team::BD)
+            );
+        }
+        Event::NewRound { chooser } => println!(
+            "A new round begins, {} chooses the trump",
+            game.player(*chooser).name
         ),
-        true => println!("Your partner has chibré."),
-    }
-    display_vec_cards(&players[index].hand);
-    loop {
-        let stdin = io::stdin();
-        let mut c = String::new();
-        println!("Choose a suit (P: ♠, C: ♥, Q: ♦, F: ♣ | S: scheiber)");
-        stdin.read_line(&mut c).expect("failed to read line");
-        let _: char = match c.trim().parse() {
-            Ok(c) => match c {
-                'P' | 'p' => return Suit::Spades,
-                'C' | 'c' => return Suit::Hearts,
-                'Q' | 'q' => return Suit::Diamonds,
-                'F' | 'f' => return Suit::Clubs,
-                'S' | 's' => match was_geschorben {
-                    true => continue,
-                    false => match index {
-                        0 => return ask_for_trump(players, 2, true),
-                        1 => return ask_for_trump(players, 3, true),
-                        2 => return ask_for_trump(players, 0, true),
-                        3 => return ask_for_trump(players, 1, true),
-                        _ => unreachable!("ask_trump()"),
-                    },
-                },
-                _ => continue,
-            },
-            Err(_) => continue,
-        };
+        Event::DeclaredOut { team } => println!("Team {:?} declares out", team),
+        Event::MatchOver {
+            winner,
+            points_ac,
+            points_bd,
+        } => println!(
+            "match over, team {:?} wins -- points_ac: {}  points_bd: {}",
+            winner, points_ac, points_bd
+        ),
+        _ => (),
     }
 }
 
-pub fn play_round() {
-    let mut rng = thread_rng();
-    let mut deck = ALL_CARDS.clone();
-    let mut points_ac: u32 = 0;
-    let mut points_bd: u32 = 0;
-    deck.shuffle(&mut rng);
-    let names = [
+fn default_names() -> [String; 4] {
+    [
         "Alice".to_string(),
         "Bob".to_string(),
         "Charlie".to_string(),
         "Darlene".to_string(),
-    ];
-    let mut players = distribute_and_create_players(deck, names);
-    let mut finished = false;
-    //TODO idx needs to increase by one at the beginning of each consecutive round
-    let mut idx = 0; //the winner begins the next fold
-    let trump_suit = ask_for_trump(&players, idx, false);
-    while !finished {
-        let mut played_cards = Vec::<TurnInfo>::new(); //usize correspond to the index of the player who played this card for example 0 -> player A
-        let mut bottom_suit: Option<Suit> = None;
-        for i in RangeInclusive::new(0, 3).map(|x| (x + idx) % 4) {
-            let tmp_played_cards = played_cards.iter().map(|x| x.card).collect();
-            let played_card = players[i].play_turn(&tmp_played_cards, trump_suit, &mut bottom_suit);
-            played_cards.push(TurnInfo::new(
-                played_card,
-                i,
-                trump_suit,
-                bottom_suit.unwrap(),
-            ));
-        }
-        //for debug only: show cards played and the one that won
-        print!("the played cards were...");
-        display_vec_cards(&played_cards.iter().map(|x| x.card).collect());
-        //dbg!(&played_cards);
-
-        played_cards.sort_by_key(|x| x.power);
-        played_cards.reverse();
-        print!("after sorting");
-        display_vec_cards(&played_cards.iter().map(|x| x.card).collect());
-        //for turn_info in played_cards.iter() {
-        //    print!("{}", turn_info.card.display());
-        //}
-        print!("\n");
-        println!(
-            "the winner is {}",
-            played_cards.first().unwrap().card.display()
-        );
-        //set the starting index as the winner's
-        let w: usize = played_cards.first().unwrap().index;
-        idx = w;
-        //give the points to the correct team
-        //and keep track of MATCH status
-        let mut match_ac = true;
-        let mut match_bd = true;
-        match w {
-            0 | 2 => {
-                points_ac += played_cards.iter().map(|x| x.value).sum::<u8>() as u32;
-                match_bd = false;
+    ]
+}
+
+//Four players at the same terminal
+pub fn human_seats() -> Seats {
+    [
+        Box::new(Human::new()),
+        Box::new(Human::new()),
+        Box::new(Human::new()),
+        Box::new(Human::new()),
+    ]
+}
+
+//A round dealt from the seed, which is printed so that the deal can be played again
+pub fn play_round(seats: &mut Seats, seed: u64) -> Result<Record, Error> {
+    println!("Seed of this deal: {}", seed);
+    let deal = Deal::from_deck(&shuffled_deck(&mut StdRng::seed_from_u64(seed)));
+    let mut record = play_deal(seats, &deal, 0)?;
+    record.seed = Some(seed);
+    Ok(record)
+}
+
+//The code of the deal is printed once the round is over, see Deal.
+//The returned record plays the round again, see Record
+pub fn play_deal(seats: &mut Seats, deal: &Deal, chooser: usize) -> Result<Record, Error> {
+    let record = Record::new(default_names(), *deal, chooser, Multipliers::default());
+    let game = record.start();
+    play_recorded(seats, game, record)
+}
+
+//Continues a saved round from where it was left
+pub fn resume_round(seats: &mut Seats, record: Record) -> Result<Record, Error> {
+    let game = record.replay()?;
+    play_recorded(seats, game, record)
+}
+
+//Plays until the round is over or a seat quits, the record is then as far as the round went
+fn play_recorded(
+    seats: &mut Seats,
+    mut game: GameState,
+    mut record: Record,
+) -> Result<Record, Error> {
+    loop {
+        let action = match next_action(&game, seats) {
+            Ok(Some(action)) => action,
+            Ok(None) | Err(Request::Quit) => break,
+            Err(Request::Save(path)) => {
+                save(&record, &path);
+                continue;
             }
-            1 | 3 => {
-                points_bd += played_cards.iter().map(|x| x.value).sum::<u8>() as u32;
-                match_ac = false;
+            Err(Request::DeclareOut) => {
+                println!("A single round has no target to declare out");
+                continue;
             }
-            _ => unreachable!("unreachable statement in play_round() for w"),
+            Err(Request::Fail(e)) => return Err(e),
+        };
+        match game.apply(action) {
+            Ok(events) => {
+                for event in events.iter() {
+                    display_event(&game, event);
+                    record.push(event);
+                    for (seat, controller) in seats.iter_mut().enumerate() {
+                        controller.notify(&game.view(seat), event);
+                    }
+                }
+            }
+            Err(e) => println!("{}", e),
         }
+    }
+    display_deal(&record.deal, record.chooser);
+    Ok(record)
+}
+
+//Carries out a `save` asked for at a prompt, the seat is then asked again
+fn save(record: &Record, path: &str) {
+    match fs::write(path, record.to_string()) {
+        Ok(()) => println!("Saved to {}, continue with --resume {}", path, path),
+        Err(e) => println!("Could not save to {}: {}", path, e),
+    }
+}
+
+//Enough to play the round again with play_deal
+fn display_deal(deal: &Deal, chooser: usize) {
+    println!("Deal of this round: {}, chosen by seat {}", deal, chooser);
+}
+
+//The seed of the match is printed at the start, the seed of every round when it is dealt
+//and its deal once it is over
+pub fn play_match(
+    seats: &mut Seats,
+    target: u32,
+    multipliers: Multipliers,
+    seed: u64,
+) -> Result<(), Error> {
+    println!("Seed of this match: {}", seed);
+    play_from(
+        seats,
+        Match::new_seeded(default_names(), target, multipliers, seed),
+    )?;
+    Ok(())
+}
+
+//Continues a match saved at any prompt, from the record of its current round
+pub fn resume_match(seats: &mut Seats, record: &Record) -> Result<(), Error> {
+    let jass_match = Match::resume(record)?;
+    println!(
+        "points_ac: {}  points_bd: {}",
+        jass_match.running_points(Team::AC),
+        jass_match.running_points(Team::BD)
+    );
+    play_from(seats, jass_match)?;
+    Ok(())
+}
 
-        //finally if there are no more cards to play the round is finished
-        if players[0].hand.len() <= 0 {
-            //cinq de der
-            match w {
-                0 | 2 => points_ac += 5,
-                1 | 3 => points_bd += 5,
-                _ => unreachable!("unreachable statement in play_round() for w"),
-            };
-            //full match bonus
-            assert!(!(match_ac && match_bd));
-            if match_ac {
-                println!("Full match from team AC");
-                points_ac += 100;
+//Plays until the match is over or a seat quits, and returns the match as it was left
+fn play_from(seats: &mut Seats, mut jass_match: Match) -> Result<Match, Error> {
+    display_round_seed(&jass_match);
+    loop {
+        if jass_match.winner().is_some() {
+            return Ok(jass_match);
+        }
+        let seat = jass_match.game().current_seat();
+        let (deal, chooser) = (jass_match.deal(), jass_match.game().chooser());
+        let played = match next_action(jass_match.game(), seats) {
+            Ok(Some(action)) => jass_match.apply(action),
+            Ok(None) | Err(Request::Quit) => return Ok(jass_match),
+            Err(Request::Save(path)) => {
+                save(jass_match.record(), &path);
+                continue;
             }
-            if match_bd {
-                println!("Full match from team BD");
-                points_bd += 100;
+            Err(Request::DeclareOut) => jass_match.declare_out(Team::of(seat)),
+            Err(Request::Fail(e)) => return Err(e),
+        };
+        match played {
+            Ok(events) => {
+                for event in events.iter() {
+                    display_event(jass_match.game(), event);
+                    match event {
+                        Event::RoundOver { .. } => display_deal(&deal, chooser),
+                        Event::NewRound { .. } => display_round_seed(&jass_match),
+                        _ => (),
+                    }
+                    for (seat, controller) in seats.iter_mut().enumerate() {
+                        controller.notify(&jass_match.game().view(seat), event);
+                    }
+                }
             }
-
-            finished = true; //the round is over
+            Err(e) => println!("{}", e),
         }
     }
-    println!(
-        "round over -- points_ac: {}  points_bd: {}",
-        points_ac, points_bd
-    );
+}
+
+//Enough to play the round again on its own with play_round
+fn display_round_seed(jass_match: &Match) {
+    println!("Seed of this round: {}", jass_match.round_seed());
 }
 
 #[cfg(test)]
@@ -392,20 +417,141 @@ pub(crate) mod tests {
         };
 
         let mut player = Player::new(vec![bour, small], "bob".to_string());
-        let bottom = trump;
-        player.update_playable_cards(&Vec::<Card>::new(), trump, Some(bottom));
+        let bottom = Card {
+            suit: trump,
+            number: Number::Ace,
+        };
+        player.update_playable_cards(&[bottom], GameMode::Trump(trump));
 
         assert_eq!(player.playable_cards.len(), 2);
     }
 
+    #[test]
+    fn no_trump_follows_suit() {
+        let hearts = Card {
+            suit: Suit::Hearts,
+            number: Number::Eight,
+        };
+        let spades = Card {
+            suit: Suit::Spades,
+            number: Number::Jack,
+        };
+        let mut player = Player::new(vec![hearts, spades], "bob".to_string());
+        let led_hearts = Card {
+            suit: Suit::Hearts,
+            number: Number::Ace,
+        };
+        let led_clubs = Card {
+            suit: Suit::Clubs,
+            number: Number::Ace,
+        };
+        player.update_playable_cards(&[led_hearts], GameMode::Obenabe);
+        assert_eq!(player.playable_cards.to_vec(), vec![hearts]);
+        player.update_playable_cards(&[led_clubs], GameMode::Undenufe);
+        assert_eq!(player.playable_cards.len(), 2);
+    }
+
+    #[test]
+    fn same_seed_same_deck() {
+        let deck = |seed| shuffled_deck(&mut StdRng::seed_from_u64(seed));
+        assert_eq!(deck(7), deck(7));
+        assert_ne!(deck(7), deck(8));
+    }
+
     #[test]
     fn shuffled_deck_is_different() {
         let mut deck = Vec::from(ALL_CARDS);
-        let mut rng = thread_rng();
+        let mut rng = rand::thread_rng();
         deck.shuffle(&mut rng);
         assert_ne!(deck, ALL_CARDS);
     }
 
+    //Asks the loop to save before its first card, and quits before its fifth one
+    struct SavesThenQuits {
+        path: String,
+        cards: usize,
+    }
+
+    impl PlayerController for SavesThenQuits {
+        fn schieben(&mut self, _view: &SeatView) -> Answer<bool> {
+            Ok(false)
+        }
+
+        fn choose_trump(&mut self, _view: &SeatView) -> Answer<GameMode> {
+            Ok(GameMode::Obenabe)
+        }
+
+        fn choose_card(&mut self, _view: &SeatView, legal_cards: &[Card]) -> Answer<Card> {
+            self.cards += 1;
+            match self.cards {
+                1 => Err(Request::Save(self.path.clone())),
+                6 => Err(Request::Quit),
+                _ => Ok(legal_cards[0]),
+            }
+        }
+    }
+
+    #[test]
+    fn the_loop_saves_and_quits_for_the_seats() {
+        let path = std::env::temp_dir().join("jass_save_and_quit.txt");
+        let path = path.to_str().unwrap().to_string();
+        let mut seats = fixtures::seats(|_| SavesThenQuits {
+            path: path.clone(),
+            cards: 0,
+        });
+        let record = play_deal(&mut seats, &fixtures::sorted_deal(), 0).unwrap();
+        let saved: Record = fs::read_to_string(&path).unwrap().parse().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved.mode, Some(GameMode::Obenabe));
+        //the last seat to save is the fourth one of the first fold
+        assert_eq!(saved.folds[0].cards.len(), 3);
+        //four folds are over when a seat quits on its fifth card
+        let over = record.folds.iter().filter(|fold| fold.winner.is_some());
+        assert_eq!(over.count(), 4);
+        assert!(record.replay().is_ok());
+    }
+
+    //Chooses Obenabe and declares out instead of playing a card
+    struct DeclaresOut;
+
+    impl PlayerController for DeclaresOut {
+        fn schieben(&mut self, _view: &SeatView) -> Answer<bool> {
+            Ok(false)
+        }
+
+        fn choose_trump(&mut self, _view: &SeatView) -> Answer<GameMode> {
+            Ok(GameMode::Obenabe)
+        }
+
+        fn choose_card(&mut self, _view: &SeatView, _legal_cards: &[Card]) -> Answer<Card> {
+            Err(Request::DeclareOut)
+        }
+    }
+
+    #[test]
+    fn a_seat_declares_out_at_its_prompt() {
+        let mut seats = fixtures::seats(|_| DeclaresOut);
+        let jass_match = Match::new_seeded(fixtures::names(), 1000, Multipliers::default(), 1);
+        let jass_match = play_from(&mut seats, jass_match).unwrap();
+        //Alice leads the first fold, far from the target
+        assert_eq!(jass_match.winner(), Some(Team::BD));
+    }
+
+    #[test]
+    fn resumes_a_saved_round() {
+        let mut seats = fixtures::seats(|_| bot::HeuristicBot::new());
+        let deal = fixtures::shuffled_deal(3);
+        let full = play_deal(&mut seats, &deal, 0).unwrap();
+        let mut saved: Record = full.to_string().parse().unwrap();
+        saved.folds.truncate(4);
+        saved.folds[3].cards.truncate(2);
+        saved.folds[3].winner = None;
+        let resumed = resume_round(&mut seats, saved).unwrap();
+        assert_eq!(resumed.folds[..3], full.folds[..3]);
+        assert_eq!(resumed.folds.len(), 9);
+        assert_eq!(resumed.replay().unwrap().phase(), Phase::Finished);
+    }
+
     #[test]
     fn hand_play() {
         let a = Card {
@@ -426,6 +572,34 @@ pub(crate) mod tests {
         assert_eq!(played, a_copy);
         assert_eq!(player.hand.len(), 1);
         let played = player.discard(&a_copy);
-        assert_eq!(played, Err("Card not found"));
+        assert_eq!(played, Err(Error::CardNotInHand(a_copy)));
+    }
+
+    #[test]
+    fn select_card_by_name_or_position() {
+        let king = Card {
+            number: Number::King,
+            suit: Suit::Hearts,
+        };
+        let ten = Card {
+            number: Number::Ten,
+            suit: Suit::Diamonds,
+        };
+        let player = Player::new(vec![king, ten], "Jerry".to_string());
+        for answer in ["KH", "K♥", "roi coeur", "king of hearts", "2"] {
+            assert_eq!(player.select_card(answer), Ok(king));
+        }
+        assert_eq!(player.select_card("10D"), Ok(ten));
+        assert_eq!(player.select_card("1"), Ok(ten));
+        assert_eq!(player.select_card("0"), Err(Error::NoCardAt(0)));
+        assert_eq!(player.select_card("3"), Err(Error::NoCardAt(3)));
+        assert!(matches!(
+            player.select_card("AH"),
+            Err(Error::CardNotInHand(_))
+        ));
+        assert!(matches!(
+            player.select_card("hello"),
+            Err(Error::ParseCard(_))
+        ));
     }
 }
@@ -1,3 +1,124 @@
+use jass_game::bot::{HeuristicBot, IsmctsBot, PimcBot, RandomBot};
+use jass_game::controller::{Human, PlayerController, Seats};
+use jass_game::error::Error;
+use jass_game::game::Multipliers;
+use jass_game::jass_match::TARGETS;
+use jass_game::record::Record;
+use jass_game::viewer::Viewer;
+
+fn read_record(path: &str) -> Result<Record, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if !cfg!(feature = "serde") && text.trim_start().starts_with('{') {
+        return Err("JSON records need a build with --features serde".to_string());
+    }
+    Record::read(&text).map_err(|e| e.to_string())
+}
+
+//The human in seat 0 against three bots of the given kind, None for an unknown kind
+fn seats_against(bots: &str) -> Option<Seats> {
+    let bot = || -> Option<Box<dyn PlayerController>> {
+        let seed = rand::random();
+        Some(match bots {
+            "random" => Box::new(RandomBot::from_seed(seed)),
+            "heuristic" => Box::new(HeuristicBot::new()),
+            "pimc" => Box::new(PimcBot::from_seed(seed)),
+            "ismcts" => Box::new(IsmctsBot::from_seed(seed)),
+            _ => return None,
+        })
+    };
+    Some([Box::new(Human::new()), bot()?, bot()?, bot()?])
+}
+
+fn play_match(seats: &mut Seats, target: u32, seed: u64) -> Result<(), Error> {
+    jass_game::play_match(seats, target, Multipliers::default(), seed)
+}
+
+const USAGE: &str = "\
+Usage: jass_game [options]
+  --seed <number>    deals the same match again, the seed is printed at the start of every match
+  --target <points>  the points to reach to win a match: 1000 (the default), 1500 or 2500
+  --deal <code>      plays a single round of the given deal, as printed after every round
+  --chooser <seat>   with --deal, gives the choice of trump to another seat than 0
+  --bots <kind>      the three other players: random, heuristic (the default), pimc or ismcts
+  --replay <file>    steps through a recorded round, JSON records need a build with --features serde
+  --resume <file>    continues a round or a match saved with `save` at any prompt
+  --help             shows this";
+
 fn main() {
-    jass_game::play_round();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let option = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .map(|i| args.get(i + 1).map_or("", |s| s.as_str()))
+    };
+    let target = match option("--target") {
+        None => TARGETS[0],
+        Some(target) => match target.parse().ok().filter(|t| TARGETS.contains(t)) {
+            Some(target) => target,
+            None => {
+                let targets: Vec<String> = TARGETS.iter().map(|t| t.to_string()).collect();
+                println!("--target needs one of {}", targets.join(", "));
+                return;
+            }
+        },
+    };
+    if option("--chooser").is_some() && option("--deal").is_none() {
+        println!("--chooser only works with --deal");
+        return;
+    }
+    let bots = option("--bots").unwrap_or("heuristic");
+    let mut seats = match seats_against(bots) {
+        Some(seats) => seats,
+        None => {
+            println!("--bots needs random, heuristic, pimc or ismcts");
+            return;
+        }
+    };
+    if option("--help").is_some() {
+        println!("{}", USAGE);
+    } else if let Some(code) = option("--deal") {
+        let chooser = option("--chooser").map_or(Some(0), |seat| {
+            seat.parse().ok().filter(|&seat: &usize| seat < 4)
+        });
+        match (code.parse(), chooser) {
+            (Ok(deal), Some(chooser)) => {
+                if let Err(e) = jass_game::play_deal(&mut seats, &deal, chooser) {
+                    println!("{}", e);
+                }
+            }
+            (Err(e), _) => println!("Invalid deal: {}", e),
+            (_, None) => println!("--chooser needs a seat from 0 to 3"),
+        }
+    } else if let Some(path) = option("--replay") {
+        match read_record(path) {
+            Ok(record) => {
+                if let Err(e) = Viewer::new(&record).run() {
+                    println!("{}", e);
+                }
+            }
+            Err(e) => println!("Cannot read the record {}: {}", path, e),
+        }
+    } else if let Some(path) = option("--resume") {
+        let resumed = read_record(path).and_then(|record| {
+            match record.standing {
+                Some(_) => jass_game::resume_match(&mut seats, &record),
+                None => jass_game::resume_round(&mut seats, record).map(|_| ()),
+            }
+            .map_err(|e| e.to_string())
+        });
+        if let Err(e) = resumed {
+            println!("Cannot resume {}: {}", path, e);
+        }
+    } else if let Some(seed) = option("--seed") {
+        match seed.parse() {
+            Ok(seed) => {
+                if let Err(e) = play_match(&mut seats, target, seed) {
+                    println!("{}", e);
+                }
+            }
+            Err(e) => println!("Invalid seed: {}", e),
+        }
+    } else if let Err(e) = play_match(&mut seats, target, rand::random()) {
+        println!("{}", e);
+    }
 }
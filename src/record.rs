@@ -0,0 +1,647 @@
+use crate::card::*;
+use crate::deal::Deal;
+use crate::error::Error;
+use crate::game::*;
+use std::fmt;
+use std::str::FromStr;
+
+//Written at the top of every record, a record of another version is refused
+pub const RECORD_VERSION: u32 = 1;
+
+//Everything needed to play a round again: who sat where, the deal, and every decision in order.
+//The text form, one line per item, looks like
+//
+//  jass-record 1
+//  player 0 Alice
+//  ...
+//  seed 1234
+//  match 99 1000 2 312 245
+//  deal 6QK♣7♦8TQ♥9A♠ 789TJA♣68♦K♥ 9TJQKA♦679♥ JA♥678TJQK♠
+//  multipliers 1 1 1 1 1 1
+//  chooser 0
+//  schieben
+//  trump ♥
+//  weis 2 789♥ JJJJ
+//  fold 0 K♥ A♥ 6♥ 7♥ -> 1 24
+//
+//where a fold gives its leader, the cards from the leader on and, once over, its winner and points.
+//The multipliers are those of clubs, diamonds, hearts, spades, Obenabe and Undenufe.
+//A round of a match also has a match line, see Standing.
+//Records are also written and read as JSON, but only when the crate is built with the serde feature
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "UncheckedRecord")
+)]
+pub struct Record {
+    pub version: u32,
+    pub names: [String; 4],
+    pub seed: Option<u64>,
+    pub standing: Option<Standing>, //only for a round of a match
+    pub deal: Deal,
+    pub multipliers: Multipliers,
+    pub chooser: usize,
+    pub geschoben: bool,
+    pub mode: Option<GameMode>,
+    pub weis: [Vec<Meld>; 4],
+    pub folds: Vec<RecordedFold>,
+}
+
+//The fields of a Record as they are read, before they are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedRecord {
+    version: u32,
+    names: [String; 4],
+    seed: Option<u64>,
+    standing: Option<Standing>,
+    deal: Deal,
+    multipliers: Multipliers,
+    chooser: usize,
+    geschoben: bool,
+    mode: Option<GameMode>,
+    weis: [Vec<Meld>; 4],
+    folds: Vec<RecordedFold>,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<UncheckedRecord> for Record {
+    type Error = Error;
+
+    //The melds are checked as they are read, the moves themselves when the record is replayed
+    fn try_from(record: UncheckedRecord) -> Result<Record, Error> {
+        let seats = record.folds.iter().flat_map(|fold| {
+            let seats = fold.cards.iter().map(|&(seat, _)| seat);
+            seats.chain(fold.winner)
+        });
+        if let Some(seat) = std::iter::once(record.chooser)
+            .chain(seats)
+            .find(|&seat| seat >= 4)
+        {
+            return Err(Error::NoSuchSeat(seat));
+        }
+        if record.folds.len() > 9 {
+            return Err(Error::Inconsistent("the round is over after nine folds"));
+        }
+        if record
+            .folds
+            .iter()
+            .any(|fold| fold.cards.is_empty() || fold.cards.len() > 4)
+        {
+            return Err(Error::Inconsistent("a fold holds one to four cards"));
+        }
+        Ok(Record {
+            version: record.version,
+            names: record.names,
+            seed: record.seed,
+            standing: record.standing,
+            deal: record.deal,
+            multipliers: record.multipliers,
+            chooser: record.chooser,
+            geschoben: record.geschoben,
+            mode: record.mode,
+            weis: record.weis,
+            folds: record.folds,
+        })
+    }
+}
+
+//Where the match stood when the round was dealt: its seed and target, the number of rounds
+//already over and their points for AC and BD. Written as `match <seed> <target> <rounds> <ac> <bd>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standing {
+    pub seed: u64,
+    pub target: u32,
+    pub rounds: usize,
+    pub points_ac: u32,
+    pub points_bd: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedFold {
+    pub cards: Vec<(usize, Card)>,
+    //None while the fold is being played
+    pub winner: Option<usize>,
+    pub points: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    Version(u32),
+    //1-based line number of the text
+    Line(usize, String),
+    Missing(&'static str),
+    //Replaying the record gave another winner or other points for this 1-based fold
+    Mismatch(usize),
+    NoSuchFold(usize),
+    //The seed of the match does not deal the recorded round
+    NotInMatch,
+    Json(String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Version(v) => write!(
+                f,
+                "version {} of the record is not supported, only {}",
+                v, RECORD_VERSION
+            ),
+            RecordError::Line(n, line) => write!(f, "cannot read line {}: '{}'", n, line),
+            RecordError::Missing(what) => write!(f, "the record has no {}", what),
+            RecordError::Mismatch(fold) => {
+                write!(f, "fold {} does not end as recorded when replayed", fold)
+            }
+            RecordError::NoSuchFold(fold) => write!(f, "fold {} was not recorded", fold),
+            RecordError::NotInMatch => write!(f, "the round is not one of the recorded match"),
+            RecordError::Json(e) => write!(f, "invalid JSON: {}", e),
+        }
+    }
+}
+
+impl Record {
+    pub fn new(names: [String; 4], deal: Deal, chooser: usize, multipliers: Multipliers) -> Record {
+        Record {
+            version: RECORD_VERSION,
+            names,
+            seed: None,
+            standing: None,
+            deal,
+            multipliers,
+            chooser,
+            geschoben: false,
+            mode: None,
+            weis: Default::default(),
+            folds: Vec::new(),
+        }
+    }
+
+    //Meant to be given every event of the round, as they come out of GameState::apply
+    pub fn push(&mut self, event: &Event) {
+        match event {
+            Event::Geschoben { .. } => self.geschoben = true,
+            Event::TrumpChosen { mode, .. } => self.mode = Some(*mode),
+            Event::WeisAnnounced { seat, melds } => self.weis[*seat] = melds.clone(),
+            Event::CardPlayed { seat, card } => {
+                match self.folds.last_mut() {
+                    Some(fold) if fold.winner.is_none() => fold.cards.push((*seat, *card)),
+                    _ => self.folds.push(RecordedFold {
+                        cards: vec![(*seat, *card)],
+                        winner: None,
+                        points: 0,
+                    }),
+                };
+            }
+            Event::TrickWon { seat, points, .. } => {
+                if let Some(fold) = self.folds.last_mut() {
+                    fold.winner = Some(*seat);
+                    fold.points = *points;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    //Every action of the round in order, with the seat taking it
+    pub fn actions(&self) -> Vec<(usize, Action)> {
+        let mut actions = Vec::new();
+        let mut chooser = self.chooser;
+        if self.geschoben {
+            actions.push((chooser, Action::Schieben));
+            chooser = (chooser + 2) % 4;
+        }
+        if let Some(mode) = self.mode {
+            actions.push((chooser, Action::ChooseTrump(mode)));
+        }
+        for (i, fold) in self.folds.iter().enumerate() {
+            for &(seat, card) in fold.cards.iter() {
+                if i == 0 && !self.weis[seat].is_empty() {
+                    actions.push((seat, Action::AnnounceWeis(self.weis[seat].clone())));
+                }
+                actions.push((seat, Action::PlayCard(card)));
+            }
+        }
+        actions
+    }
+
+    //The round as it was dealt, before anyone acted
+    pub fn start(&self) -> GameState {
+        GameState::new_with_multipliers(
+            self.deal.players(self.names.clone()),
+            self.chooser,
+            self.multipliers,
+        )
+    }
+
+    //Plays every recorded action again, checking that the folds end as recorded
+    pub fn replay(&self) -> Result<GameState, Error> {
+        self.replay_until(|_| false)
+    }
+
+    //The round once the given number of folds are over, 0 being right after the trump choice
+    pub fn replay_to(&self, folds: usize) -> Result<GameState, Error> {
+        let recorded = self.folds.iter().filter(|f| f.winner.is_some()).count();
+        if folds > recorded {
+            return Err(RecordError::NoSuchFold(folds).into());
+        }
+        self.replay_until(|game| {
+            game.tricks().len() == folds && game.table().is_empty() && game.mode().is_some()
+        })
+    }
+
+    fn replay_until<F: Fn(&GameState) -> bool>(&self, stop: F) -> Result<GameState, Error> {
+        let mut game = self.start();
+        for (seat, action) in self.actions() {
+            if stop(&game) {
+                break;
+            }
+            for event in game.apply_as(seat, action)? {
+                if let Event::TrickWon { seat, points, .. } = event {
+                    let n = game.tricks().len();
+                    let fold = &self.folds[n - 1];
+                    if fold.winner != Some(seat) || fold.points != points {
+                        return Err(RecordError::Mismatch(n).into());
+                    }
+                }
+            }
+        }
+        Ok(game)
+    }
+
+    //The text form, or JSON when built with the serde feature and the text is an object
+    pub fn read(text: &str) -> Result<Record, Error> {
+        #[cfg(feature = "serde")]
+        if text.trim_start().starts_with('{') {
+            return Record::from_json(text);
+        }
+        text.parse()
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a record is always valid JSON")
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Record, Error> {
+        let record: Record =
+            serde_json::from_str(json).map_err(|e| RecordError::Json(e.to_string()))?;
+        match record.version {
+            RECORD_VERSION => Ok(record),
+            v => Err(RecordError::Version(v).into()),
+        }
+    }
+}
+
+//A sequence as its numbers followed by the suit, as in 789♥, four of a kind as JJJJ
+fn write_meld(meld: &Meld) -> String {
+    match *meld {
+        Meld::Sequence { suit, .. } => {
+            let numbers: String = meld.cards().iter().map(|c| c.number.to_string()).collect();
+            format!("{}{}", numbers, suit)
+        }
+        Meld::FourOfAKind(number) => number.to_string().repeat(4),
+    }
+}
+
+fn parse_meld(text: &str) -> Option<Meld> {
+    let chars: Vec<String> = text.chars().map(|c| c.to_string()).collect();
+    let (last, rest) = chars.split_last()?;
+    let numbers: Vec<Number> = rest.iter().map(|c| c.parse().ok()).collect::<Option<_>>()?;
+    let high = *numbers.last()?;
+    match last.parse::<Suit>() {
+        Ok(suit) => {
+            let meld = Meld::Sequence {
+                suit,
+                high,
+                length: numbers.len(),
+            };
+            let consecutive = (3..=9).contains(&numbers.len())
+                && numbers
+                    .windows(2)
+                    .all(|w| w[0] as usize + 1 == w[1] as usize);
+            consecutive.then_some(meld)
+        }
+        Err(_) => {
+            let number: Number = last.parse().ok()?;
+            let meld = Meld::FourOfAKind(number);
+            (numbers.len() == 3 && numbers.iter().all(|&n| n == number) && meld.is_possible())
+                .then_some(meld)
+        }
+    }
+}
+
+fn parse_seat(text: &str) -> Option<usize> {
+    text.parse().ok().filter(|&seat| seat < 4)
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "jass-record {}", self.version)?;
+        for (seat, name) in self.names.iter().enumerate() {
+            writeln!(f, "player {} {}", seat, name)?;
+        }
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed {}", seed)?;
+        }
+        if let Some(s) = self.standing {
+            writeln!(
+                f,
+                "match {} {} {} {} {}",
+                s.seed, s.target, s.rounds, s.points_ac, s.points_bd
+            )?;
+        }
+        writeln!(f, "deal {}", self.deal)?;
+        let m = self.multipliers;
+        writeln!(
+            f,
+            "multipliers {} {} {} {} {} {}",
+            m.clubs, m.diamonds, m.hearts, m.spades, m.obenabe, m.undenufe
+        )?;
+        writeln!(f, "chooser {}", self.chooser)?;
+        if self.geschoben {
+            writeln!(f, "schieben")?;
+        }
+        if let Some(mode) = self.mode {
+            writeln!(f, "trump {}", mode)?;
+        }
+        for (seat, melds) in self.weis.iter().enumerate() {
+            if !melds.is_empty() {
+                let melds: Vec<String> = melds.iter().map(write_meld).collect();
+                writeln!(f, "weis {} {}", seat, melds.join(" "))?;
+            }
+        }
+        for fold in self.folds.iter() {
+            write!(
+                f,
+                "fold {}",
+                fold.cards.first().map_or(0, |&(seat, _)| seat)
+            )?;
+            for (_, card) in fold.cards.iter() {
+                write!(f, " {}", card)?;
+            }
+            match fold.winner {
+                Some(winner) => writeln!(f, " -> {} {}", winner, fold.points)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+//Reads one line of the text form into the record, None if it makes no sense
+fn parse_line(record: &mut Record, line: &str, names: &mut [Option<String>; 4]) -> Option<()> {
+    let (key, rest) = match line.split_once(char::is_whitespace) {
+        Some((key, rest)) => (key, rest.trim()),
+        None => (line, ""),
+    };
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    match key {
+        "player" => {
+            let (seat, name) = rest.split_once(char::is_whitespace)?;
+            names[parse_seat(seat)?] = Some(name.trim().to_string());
+        }
+        "seed" => record.seed = Some(rest.parse().ok()?),
+        "match" => match words[..] {
+            [seed, target, rounds, points_ac, points_bd] => {
+                record.standing = Some(Standing {
+                    seed: seed.parse().ok()?,
+                    target: target.parse().ok()?,
+                    rounds: rounds.parse().ok()?,
+                    points_ac: points_ac.parse().ok()?,
+                    points_bd: points_bd.parse().ok()?,
+                })
+            }
+            _ => return None,
+        },
+        "deal" => (), //read before the other lines
+        "multipliers" => {
+            let m: Vec<u32> = words
+                .iter()
+                .map(|w| w.parse().ok())
+                .collect::<Option<_>>()?;
+            if m.len() != 6 {
+                return None;
+            }
+            record.multipliers = Multipliers {
+                clubs: m[0],
+                diamonds: m[1],
+                hearts: m[2],
+                spades: m[3],
+                obenabe: m[4],
+                undenufe: m[5],
+            };
+        }
+        "chooser" => record.chooser = parse_seat(rest)?,
+        "schieben" if rest.is_empty() => record.geschoben = true,
+        "trump" => record.mode = Some(rest.parse().ok()?),
+        "weis" => {
+            let (seat, melds) = words.split_first()?;
+            record.weis[parse_seat(seat)?] =
+                melds.iter().map(|m| parse_meld(m)).collect::<Option<_>>()?;
+        }
+        "fold" => {
+            let (leader, rest) = words.split_first()?;
+            let leader = parse_seat(leader)?;
+            let (cards, end) = match rest.iter().position(|&w| w == "->") {
+                Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+                None => (rest, None),
+            };
+            let cards: Vec<Card> = cards
+                .iter()
+                .map(|c| c.parse().ok())
+                .collect::<Option<_>>()?;
+            if cards.is_empty() || cards.len() > 4 {
+                return None;
+            }
+            let (winner, points) = match end {
+                Some([winner, points]) => (Some(parse_seat(winner)?), points.parse().ok()?),
+                Some(_) => return None,
+                None => (None, 0),
+            };
+            record.folds.push(RecordedFold {
+                cards: (0..).map(|i| (leader + i) % 4).zip(cards).collect(),
+                winner,
+                points,
+            });
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+impl FromStr for Record {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Record, Error> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let version = match lines.next() {
+            Some((_, line)) => line
+                .strip_prefix("jass-record ")
+                .and_then(|v| v.trim().parse().ok())
+                .ok_or(RecordError::Missing("version"))?,
+            None => return Err(RecordError::Missing("version").into()),
+        };
+        if version != RECORD_VERSION {
+            return Err(RecordError::Version(version).into());
+        }
+        let lines: Vec<(usize, &str)> = lines.collect();
+        //the deal comes first as a record cannot be without one
+        let mut deal = None;
+        for &(n, line) in lines.iter() {
+            if let Some(code) = line.strip_prefix("deal ") {
+                let parsed = code.trim().parse().ok();
+                deal = Some(parsed.ok_or_else(|| RecordError::Line(n, line.to_string()))?);
+            }
+        }
+        let deal = deal.ok_or(RecordError::Missing("deal"))?;
+        let mut record = Record::new(Default::default(), deal, 0, Multipliers::default());
+        let mut names = Default::default();
+        for (n, line) in lines {
+            parse_line(&mut record, line, &mut names)
+                .ok_or_else(|| RecordError::Line(n, line.to_string()))?;
+        }
+        for (seat, name) in names.iter_mut().enumerate() {
+            record.names[seat] = name.take().ok_or(RecordError::Missing("player"))?;
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::HeuristicBot;
+    use crate::fixtures;
+
+    fn played_record() -> (Record, GameState) {
+        let deal = fixtures::shuffled_deal(42);
+        let mut record = Record::new(fixtures::names(), deal, 1, Multipliers::by_suit());
+        record.seed = Some(42);
+        let mut game = record.start();
+        let mut seats = fixtures::seats(|_| HeuristicBot::new());
+        for event in fixtures::play_out(&mut game, &mut seats) {
+            record.push(&event);
+        }
+        (record, game)
+    }
+
+    #[test]
+    fn replay_gives_the_same_round() {
+        let (record, game) = played_record();
+        assert_eq!(record.folds.len(), 9);
+        let replayed = record.replay().unwrap();
+        assert_eq!(replayed.phase(), Phase::Finished);
+        assert_eq!(replayed.summary(), game.summary());
+
+        let after_three = record.replay_to(3).unwrap();
+        assert_eq!(after_three.tricks().len(), 3);
+        assert!(after_three.table().is_empty());
+        let first = record.replay_to(0).unwrap();
+        assert_eq!(first.mode(), record.mode);
+        assert!(first.tricks().is_empty());
+        assert_eq!(
+            record.replay_to(10).err(),
+            Some(Error::Record(RecordError::NoSuchFold(10)))
+        );
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let (mut record, _) = played_record();
+        record.weis[2] = vec![
+            Meld::Sequence {
+                suit: Suit::Hearts,
+                high: Number::Nine,
+                length: 4,
+            },
+            Meld::FourOfAKind(Number::Jack),
+        ];
+        record.standing = Some(Standing {
+            seed: 99,
+            target: 1000,
+            rounds: 2,
+            points_ac: 312,
+            points_bd: 245,
+        });
+        let text = record.to_string();
+        assert!(text.starts_with("jass-record 1\nplayer 0 Alice\n"));
+        assert!(text.contains("weis 2 6789♥ JJJJ\n"));
+        assert!(text.contains("match 99 1000 2 312 245\n"));
+        assert_eq!(text.parse(), Ok(record));
+
+        let unfinished = "jass-record 1\nplayer 0 A\nplayer 1 B\nplayer 2 C\nplayer 3 D\n\
+            deal 6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQKA♠\nchooser 0\ntrump obenabe\n\
+            fold 0 AF";
+        let record: Record = unfinished.parse().unwrap();
+        assert_eq!(record.folds[0].winner, None);
+        assert_eq!(record.replay().unwrap().table().len(), 1);
+    }
+
+    #[test]
+    fn refuses_bad_records() {
+        let (record, _) = played_record();
+        let text = record.to_string();
+        assert_eq!(
+            text.replace("jass-record 1", "jass-record 2")
+                .parse::<Record>(),
+            Err(Error::Record(RecordError::Version(2)))
+        );
+        assert_eq!(
+            text.replace("chooser 1", "chooser 7").parse::<Record>(),
+            Err(Error::Record(RecordError::Line(9, "chooser 7".to_string())))
+        );
+
+        let mut wrong = record.clone();
+        wrong.folds[4].points += 1;
+        assert_eq!(
+            wrong.replay().err(),
+            Some(Error::Record(RecordError::Mismatch(5)))
+        );
+        let mut wrong = record;
+        wrong.folds[0].cards.swap(0, 1);
+        assert!(matches!(
+            wrong.replay().err(),
+            Some(Error::OutOfTurn { .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let (record, _) = played_record();
+        assert_eq!(Record::from_json(&record.to_json()), Ok(record));
+        assert!(Record::from_json("{}").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_is_checked() {
+        let (record, _) = played_record();
+        let json = record.to_json();
+        let chooser = format!("\"chooser\": {}", record.chooser);
+        assert!(json.contains(&chooser));
+        let wrong = json.replace(&chooser, "\"chooser\": 7");
+        assert!(matches!(
+            Record::from_json(&wrong),
+            Err(Error::Record(RecordError::Json(_)))
+        ));
+        let mut wrong = record.clone();
+        wrong.folds[2].cards[1].0 = 4;
+        assert!(Record::from_json(&wrong.to_json()).is_err());
+        let mut wrong = record;
+        wrong.weis[0] = vec![Meld::Sequence {
+            suit: Suit::Clubs,
+            high: Number::Six,
+            length: 9,
+        }];
+        assert!(Record::from_json(&wrong.to_json()).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+use std::fmt;
+
+//Why a card in hand cannot be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalReason {
+    MustFollowSuit(Suit),
+    TrumpWasLed(Suit),
+    UndertrumpForbidden,
+}
+
+impl fmt::Display for IllegalReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IllegalReason::MustFollowSuit(s) => write!(
+                f,
+                "{} was led and you still have some, you must follow the suit",
+                s.symbol()
+            ),
+            IllegalReason::TrumpWasLed(s) => write!(
+                f,
+                "trump ({}) was led and you still have some, you must play trump",
+                s.symbol()
+            ),
+            IllegalReason::UndertrumpForbidden => write!(
+                f,
+                "you may not play a trump lower than the one already on the table"
+            ),
+        }
+    }
+}
+
+//Checks a card of the hand against the official Schieber rules:
+// - the first card of a fold is free
+// - without trump you must follow the suit if you can
+// - when trump is led you must play trump if you have some, unless the Bour is your only trump
+// - otherwise you follow the suit or play a trump, even if you could follow
+// - you may not play a trump lower than one already on the table, unless you hold nothing but trumps
+pub fn check_card(
+    hand: &[Card],
+    table: &[Card],
+    mode: GameMode,
+    card: Card,
+) -> Result<(), IllegalReason> {
+    let bottom = match table.first() {
+        None => return Ok(()),
+        Some(c) => c.suit,
+    };
+    let can_follow = hand.iter().any(|c| c.suit == bottom);
+    let trump = match mode.trump() {
+        Some(trump) => trump,
+        None if card.suit == bottom || !can_follow => return Ok(()),
+        None => return Err(IllegalReason::MustFollowSuit(bottom)),
+    };
+    if bottom == trump {
+        let bour = Card {
+            suit: trump,
+            number: Number::Jack,
+        };
+        let only_bour = hand.iter().all(|c| c.suit != trump || *c == bour);
+        return match card.suit == trump || !can_follow || only_bour {
+            true => Ok(()),
+            false => Err(IllegalReason::TrumpWasLed(trump)),
+        };
+    }
+
+    if card.suit == trump {
+        let highest_trump = table
+            .iter()
+            .filter(|c| c.suit == trump)
+            .map(|c| c.power(mode, bottom))
+            .max();
+        let only_trumps = hand.iter().all(|c| c.suit == trump);
+        match highest_trump {
+            Some(highest) if !only_trumps && card.power(mode, bottom) < highest => {
+                Err(IllegalReason::UndertrumpForbidden)
+            }
+            _ => Ok(()),
+        }
+    } else if card.suit == bottom || !can_follow {
+        Ok(())
+    } else {
+        Err(IllegalReason::MustFollowSuit(bottom))
+    }
+}
+
+//The cards of the hand that may be played on the table, with the same rules as check_card
+pub fn legal_set(hand: CardSet, table: &[Card], mode: GameMode) -> CardSet {
+    let bottom = match table.first() {
+        None => return hand,
+        Some(c) => c.suit,
+    };
+    let follow = hand.suit(bottom);
+    let trump = match mode.trump() {
+        None if follow.is_empty() => return hand,
+        None => return follow,
+        Some(trump) => trump,
+    };
+    let trumps = hand.suit(trump);
+    if bottom == trump {
+        let bour = CardSet::single(Card {
+            suit: trump,
+            number: Number::Jack,
+        });
+        return match follow.is_empty() || trumps == bour {
+            true => hand,
+            false => trumps,
+        };
+    }
+
+    let others = match follow.is_empty() {
+        true => hand - trumps,
+        false => follow,
+    };
+    let highest_trump = table
+        .iter()
+        .filter(|c| c.suit == trump)
+        .map(|c| c.power(mode, bottom))
+        .max();
+    match highest_trump {
+        Some(highest) if trumps != hand => {
+            others
+                | trumps
+                    .iter()
+                    .filter(|c| c.power(mode, bottom) > highest)
+                    .collect()
+        }
+        _ => others | trumps,
+    }
+}
+
+//The cards of the hand that may be played on the table, in the order of the hand
+pub fn legal_cards(hand: &[Card], table: &[Card], mode: GameMode) -> Vec<Card> {
+    let legal = legal_set(hand.into(), table, mode);
+    hand.iter()
+        .filter(|&&c| legal.contains(c))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::card;
+
+    const TRUMP: GameMode = GameMode::Trump(Suit::Spades);
+
+    #[test]
+    fn first_card_is_free() {
+        let hand = ALL_CARDS[..9].to_vec();
+        assert_eq!(legal_cards(&hand, &[], TRUMP), hand);
+    }
+
+    #[test]
+    fn follow_suit_or_trump_in() {
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Ace, Suit::Hearts),
+            card(Number::Seven, Suit::Spades),
+        ];
+        let table = [card(Number::King, Suit::Clubs)];
+        assert_eq!(legal_cards(&hand, &table, TRUMP), vec![hand[0], hand[2]]);
+        //no trump to play in
+        assert_eq!(legal_cards(&hand, &table, GameMode::Obenabe), vec![hand[0]]);
+    }
+
+    #[test]
+    fn undertrump_forbidden() {
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Ace, Suit::Hearts),
+            card(Number::Seven, Suit::Spades),
+            card(Number::Nine, Suit::Spades),
+        ];
+        let table = [
+            card(Number::King, Suit::Hearts),
+            card(Number::Ten, Suit::Spades),
+        ];
+        //the Nine is above the Ten in trump, the Seven is not
+        assert_eq!(legal_cards(&hand, &table, TRUMP), vec![hand[1], hand[3]]);
+        //without the suit, anything but the undertrump
+        let table = [
+            card(Number::King, Suit::Diamonds),
+            card(Number::Ten, Suit::Spades),
+        ];
+        assert_eq!(
+            legal_cards(&hand, &table, TRUMP),
+            vec![hand[0], hand[1], hand[3]]
+        );
+    }
+
+    #[test]
+    fn undertrump_with_only_trumps() {
+        let hand = vec![
+            card(Number::Six, Suit::Spades),
+            card(Number::Seven, Suit::Spades),
+        ];
+        let table = [
+            card(Number::King, Suit::Hearts),
+            card(Number::Ten, Suit::Spades),
+        ];
+        assert_eq!(legal_cards(&hand, &table, TRUMP), hand);
+    }
+
+    #[test]
+    fn trump_led() {
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Six, Suit::Spades),
+            card(Number::Jack, Suit::Spades),
+        ];
+        let table = [card(Number::Ace, Suit::Spades)];
+        assert_eq!(legal_cards(&hand, &table, TRUMP), vec![hand[1], hand[2]]);
+        //the Bour alone may be held back
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Jack, Suit::Spades),
+        ];
+        assert_eq!(legal_cards(&hand, &table, TRUMP), hand);
+    }
+
+    #[test]
+    fn illegal_reasons() {
+        let hand = vec![
+            card(Number::Six, Suit::Clubs),
+            card(Number::Ace, Suit::Hearts),
+            card(Number::Seven, Suit::Spades),
+        ];
+        let table = [card(Number::King, Suit::Clubs)];
+        assert_eq!(
+            check_card(&hand, &table, TRUMP, hand[1]),
+            Err(IllegalReason::MustFollowSuit(Suit::Clubs))
+        );
+        let table = [card(Number::King, Suit::Spades)];
+        assert_eq!(
+            check_card(&hand, &table, TRUMP, hand[0]),
+            Err(IllegalReason::TrumpWasLed(Suit::Spades))
+        );
+        let table = [
+            card(Number::King, Suit::Diamonds),
+            card(Number::Eight, Suit::Spades),
+        ];
+        assert_eq!(
+            check_card(&hand, &table, TRUMP, hand[2]),
+            Err(IllegalReason::UndertrumpForbidden)
+        );
+        assert_eq!(
+            IllegalReason::UndertrumpForbidden.to_string(),
+            "you may not play a trump lower than the one already on the table"
+        );
+    }
+
+    #[test]
+    fn bitsets_agree_with_check_card() {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let modes = [TRUMP, GameMode::Obenabe, GameMode::Undenufe];
+        for _ in 0..2000 {
+            let mut deck = ALL_CARDS;
+            deck.shuffle(&mut rng);
+            let table = &deck[9..9 + (deck[35].number as usize % 4)];
+            let mode = *modes.choose(&mut rng).unwrap();
+            let legal: Vec<Card> = deck[..9]
+                .iter()
+                .filter(|&&c| check_card(&deck[..9], table, mode, c).is_ok())
+                .copied()
+                .collect();
+            assert_eq!(legal_cards(&deck[..9], table, mode), legal);
+        }
+    }
+}
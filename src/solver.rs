@@ -0,0 +1,467 @@
+use crate::card::*;
+use crate::card_set::CardSet;
+use crate::deal::DealError;
+use crate::error::Error;
+use crate::game::Team;
+use crate::rules::legal_set;
+use std::collections::HashMap;
+use std::time::Instant;
+
+//The rest of a round played perfectly by everyone, all hands being open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub points_ac: u32,
+    pub points_bd: u32,
+    pub best_card: Card,
+}
+
+//Solves a round where every hand is known: `hands` are the cards still held, `leader` the seat
+//who began the current fold and `table` the cards already played in it.
+//The points count the cards on the table and the 5 of the last fold, but no match bonus nor multiplier.
+//Fails when the position cannot happen in a round, or when the side to move has no card left
+pub fn solve(
+    hands: [Vec<Card>; 4],
+    mode: GameMode,
+    leader: usize,
+    table: &[Card],
+) -> Result<Solution, Error> {
+    check_position(&hands, leader, table)?;
+    let remaining = hands
+        .iter()
+        .flatten()
+        .chain(table)
+        .map(|c| c.value(mode) as u32)
+        .sum::<u32>()
+        + 5;
+    let table = table
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| ((leader + i) % 4, c))
+        .collect::<Vec<_>>();
+    let turn = (leader + table.len()) % 4;
+    let hands = [0, 1, 2, 3].map(|seat| hands[seat].iter().collect());
+    let (best_card, points_ac) = Position::new(hands, mode, table, turn)
+        .best_move()
+        .expect("the side to move has a card to play");
+    Ok(Solution {
+        points_ac,
+        points_bd: remaining - points_ac,
+        best_card,
+    })
+}
+
+//Every card at most once, and the seats who already played in the fold holding one card fewer
+fn check_position(hands: &[Vec<Card>; 4], leader: usize, table: &[Card]) -> Result<(), Error> {
+    if leader >= 4 {
+        return Err(Error::NoSuchSeat(leader));
+    }
+    if table.len() >= 4 {
+        return Err(Error::FoldComplete);
+    }
+    let mut seen = CardSet::EMPTY;
+    for &card in hands.iter().flatten().chain(table) {
+        if !seen.insert(card) {
+            return Err(Error::Deal(DealError::DuplicateCard(card)));
+        }
+    }
+    let turn = (leader + table.len()) % 4;
+    let size = hands[turn].len();
+    if size == 0 {
+        return Err(Error::UnevenHands { seat: turn, size });
+    }
+    for (i, seat) in (0..4).map(|i| (i, (leader + i) % 4)) {
+        let expected = if i < table.len() { size - 1 } else { size };
+        if hands[seat].len() != expected {
+            return Err(Error::UnevenHands {
+                seat,
+                size: hands[seat].len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+//A position where every remaining card is known, as in a sampled deal
+#[derive(Debug, Clone)]
+pub(crate) struct Position {
+    pub hands: [CardSet; 4],
+    pub mode: GameMode,
+    pub table: Vec<(usize, Card)>, //seat and card, in the order they were played
+    pub turn: usize,
+    //bounds of the points AC makes from the start of a fold, by remaining cards and leader.
+    //Only valid for one deal: the remaining cards then tell who holds what
+    known: HashMap<u64, (i32, i32)>,
+    deadline: Option<Instant>,
+    nodes: u64,
+    timed_out: bool,
+}
+
+impl Position {
+    pub fn new(
+        hands: [CardSet; 4],
+        mode: GameMode,
+        table: Vec<(usize, Card)>,
+        turn: usize,
+    ) -> Position {
+        Position {
+            hands,
+            mode,
+            table,
+            turn,
+            known: HashMap::new(),
+            deadline: None,
+            nodes: 0,
+            timed_out: false,
+        }
+    }
+
+    //Past the deadline the search gives up and every result is None
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    //The best card for the side to move and the points AC then makes
+    pub fn best_move(&mut self) -> Option<(Card, u32)> {
+        let seat = self.turn;
+        let maximizing = Team::of(seat) == Team::AC;
+        let (mut alpha, mut beta) = (i32::MIN, i32::MAX);
+        let mut best = None;
+        for card in self.moves(seat) {
+            self.play(card);
+            //only a card doing better than the best so far needs an exact value
+            let points = self.search(alpha, beta);
+            self.take_back(seat, card);
+            let better = match best {
+                None => true,
+                Some((_, b)) if maximizing => points > b,
+                Some((_, b)) => points < b,
+            };
+            if better {
+                best = Some((card, points));
+                match maximizing {
+                    true => alpha = points,
+                    false => beta = points,
+                }
+            }
+        }
+        let (card, points) = best?;
+        self.result(points).map(|points| (card, points))
+    }
+
+    //Points team AC makes after playing the card, the other cards being played perfectly
+    pub fn solve_after(&mut self, card: Card) -> Option<u32> {
+        let seat = self.turn;
+        self.play(card);
+        let points = self.search(i32::MIN, i32::MAX);
+        self.take_back(seat, card);
+        self.result(points)
+    }
+
+    fn result(&self, points: i32) -> Option<u32> {
+        match self.timed_out {
+            true => None,
+            false => Some(points as u32),
+        }
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.timed_out && self.nodes % 4096 == 0 {
+            self.timed_out = self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.timed_out
+    }
+
+    fn play(&mut self, card: Card) {
+        let seat = self.turn;
+        self.hands[seat].remove(card);
+        self.table.push((seat, card));
+        self.turn = (seat + 1) % 4;
+    }
+
+    fn take_back(&mut self, seat: usize, card: Card) {
+        self.table.pop();
+        self.hands[seat].insert(card);
+        self.turn = seat;
+    }
+
+    fn search(&mut self, mut alpha: i32, mut beta: i32) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        if self.table.len() == 4 {
+            return self.finish_trick(alpha, beta);
+        }
+        let key = match self.table.is_empty() {
+            true => Some(self.remaining() | (self.turn as u64) << 36),
+            false => None,
+        };
+        let (alpha_start, beta_start) = (alpha, beta);
+        if let Some(&(lower, upper)) = key.and_then(|k| self.known.get(&k)) {
+            if lower >= beta || lower == upper {
+                return lower;
+            }
+            if upper <= alpha {
+                return upper;
+            }
+            alpha = alpha.max(lower);
+            beta = beta.min(upper);
+        }
+        let seat = self.turn;
+        let maximizing = Team::of(seat) == Team::AC;
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+        for card in self.moves(seat) {
+            self.play(card);
+            let points = self.search(alpha, beta);
+            self.take_back(seat, card);
+            if maximizing {
+                best = best.max(points);
+                alpha = alpha.max(points);
+            } else {
+                best = best.min(points);
+                beta = beta.min(points);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        if let Some(key) = key.filter(|_| !self.timed_out) {
+            let (lower, upper) = self
+                .known
+                .get(&key)
+                .copied()
+                .unwrap_or((i32::MIN, i32::MAX));
+            let bounds = if best <= alpha_start {
+                (lower, best.min(upper))
+            } else if best >= beta_start {
+                (best.max(lower), upper)
+            } else {
+                (best, best)
+            };
+            self.known.insert(key, bounds);
+        }
+        best
+    }
+
+    //The legal cards, the most promising first so that the search cuts early:
+    //take the fold cheaply, give points to the partner, or throw away as little as possible
+    fn moves(&self, seat: usize) -> Vec<Card> {
+        let table: Vec<Card> = self.table.iter().map(|&(_, c)| c).collect();
+        let legal = legal_set(self.hands[seat], &table, self.mode);
+        let mode = self.mode;
+        //of two cards worth the same that no other card separates, playing one or the other is the same
+        let others = (0..4)
+            .filter(|&s| s != seat)
+            .fold(table.iter().collect::<CardSet>(), |set, s| {
+                set | self.hands[s]
+            });
+        let separated = |low: Card, high: Card| {
+            others.suit(low.suit).iter().any(|c| {
+                c.power(mode, c.suit) > low.power(mode, low.suit)
+                    && c.power(mode, c.suit) < high.power(mode, high.suit)
+            })
+        };
+        let mut cards: Vec<Card> = legal
+            .iter()
+            .filter(|&low| {
+                !legal.suit(low.suit).iter().any(|high| {
+                    high.value(mode) == low.value(mode)
+                        && high.power(mode, high.suit) > low.power(mode, low.suit)
+                        && !separated(low, high)
+                })
+            })
+            .collect();
+        match self.table.first() {
+            None => cards.sort_by_key(|c| std::cmp::Reverse(c.power(mode, c.suit))),
+            Some(&(_, first)) => {
+                let bottom = first.suit;
+                let (winner, high) = self
+                    .table
+                    .iter()
+                    .map(|&(s, c)| (s, c.power(mode, bottom)))
+                    .max_by_key(|&(_, power)| power)
+                    .expect("a card was led");
+                let partner_wins = Team::of(winner) == Team::of(seat);
+                cards.sort_by_key(|c| {
+                    let power = c.power(mode, bottom) as i32;
+                    let value = c.value(mode) as i32;
+                    match (power > high as i32, partner_wins) {
+                        (_, true) => -value,
+                        (true, false) => -100 + power,
+                        (false, false) => value,
+                    }
+                });
+            }
+        }
+        cards
+    }
+
+    fn remaining(&self) -> u64 {
+        self.hands.iter().fold(0, |mask, hand| mask | hand.bits())
+    }
+
+    fn finish_trick(&mut self, alpha: i32, beta: i32) -> i32 {
+        let last = self.hands.iter().all(|h| h.is_empty());
+        let (winner, points) = score_trick(&self.table, self.mode, last);
+        let gained = match Team::of(winner) {
+            Team::AC => points as i32,
+            Team::BD => 0,
+        };
+        if last {
+            return gained;
+        }
+        let (table, turn) = (std::mem::take(&mut self.table), self.turn);
+        self.turn = winner;
+        let rest = self.search(alpha.saturating_sub(gained), beta.saturating_sub(gained));
+        self.table = table;
+        self.turn = turn;
+        gained + rest
+    }
+}
+
+//The seat taking a full trick and the points it is worth, with the 5 of the last fold
+pub(crate) fn score_trick(table: &[(usize, Card)], mode: GameMode, last: bool) -> (usize, u32) {
+    let bottom = table[0].1.suit;
+    let (winner, _) = *table
+        .iter()
+        .max_by_key(|(_, c)| c.power(mode, bottom))
+        .expect("a trick has four cards");
+    let mut points: u32 = table.iter().map(|(_, c)| c.value(mode) as u32).sum();
+    if last {
+        points += 5; //cinq de der
+    }
+    (winner, points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::card;
+
+    #[test]
+    fn last_fold() {
+        let mut position = Position::new(
+            [
+                vec![card(Number::Ace, Suit::Hearts)],
+                vec![card(Number::Six, Suit::Hearts)],
+                vec![card(Number::Ten, Suit::Hearts)],
+                vec![card(Number::Six, Suit::Spades)],
+            ]
+            .map(|h| h.into_iter().collect()),
+            GameMode::Trump(Suit::Spades),
+            Vec::new(),
+            0,
+        );
+        let mut obenabe = position.clone();
+        obenabe.mode = GameMode::Obenabe;
+        //Darlene trumps the Ace and the Ten
+        assert_eq!(position.best_move().unwrap().1, 0);
+        assert_eq!(obenabe.best_move().unwrap().1, 11 + 10 + 5);
+    }
+
+    #[test]
+    fn finds_the_better_line() {
+        //Alice should cash her Ace first so that Charlie can smear his Eight on it
+        let mut position = Position::new(
+            [
+                vec![
+                    card(Number::Ace, Suit::Hearts),
+                    card(Number::Six, Suit::Clubs),
+                ],
+                vec![
+                    card(Number::King, Suit::Hearts),
+                    card(Number::Seven, Suit::Clubs),
+                ],
+                vec![
+                    card(Number::Six, Suit::Diamonds),
+                    card(Number::Eight, Suit::Clubs),
+                ],
+                vec![
+                    card(Number::Queen, Suit::Hearts),
+                    card(Number::Ace, Suit::Clubs),
+                ],
+            ]
+            .map(|h| h.into_iter().collect()),
+            GameMode::Obenabe,
+            Vec::new(),
+            0,
+        );
+        assert_eq!(
+            position.best_move(),
+            Some((card(Number::Ace, Suit::Hearts), 11 + 4 + 3 + 8))
+        );
+        assert_eq!(
+            position.solve_after(card(Number::Six, Suit::Clubs)),
+            Some(11 + 4 + 3 + 5)
+        );
+        //nothing was changed by the search
+        assert_eq!(position.hands[0].len(), 2);
+        assert!(position.table.is_empty());
+    }
+
+    #[test]
+    fn gives_up_after_the_deadline() {
+        let deal = crate::fixtures::sorted_deal();
+        let hands = [0, 1, 2, 3].map(|seat| deal.hand(seat));
+        let mut position = Position::new(hands, GameMode::Obenabe, Vec::new(), 0);
+        position.set_deadline(Instant::now());
+        assert_eq!(position.best_move(), None);
+    }
+
+    #[test]
+    fn solves_from_the_middle_of_a_fold() {
+        //Darlene led the Queen, Alice has to take it with her Ace
+        let hands = [
+            vec![
+                card(Number::Ace, Suit::Hearts),
+                card(Number::Six, Suit::Clubs),
+            ],
+            vec![
+                card(Number::King, Suit::Hearts),
+                card(Number::Seven, Suit::Clubs),
+            ],
+            vec![
+                card(Number::Six, Suit::Diamonds),
+                card(Number::Eight, Suit::Clubs),
+            ],
+            vec![card(Number::Ace, Suit::Clubs)],
+        ];
+        let table = [card(Number::Queen, Suit::Hearts)];
+        assert_eq!(
+            solve(hands, GameMode::Obenabe, 3, &table),
+            Ok(Solution {
+                points_ac: 3 + 11 + 4 + 8,
+                points_bd: 11 + 5,
+                best_card: card(Number::Ace, Suit::Hearts),
+            })
+        );
+    }
+
+    #[test]
+    fn refuses_impossible_positions() {
+        let hands = [
+            vec![card(Number::Ace, Suit::Hearts)],
+            vec![card(Number::King, Suit::Hearts)],
+            vec![card(Number::Six, Suit::Diamonds)],
+            vec![card(Number::Ace, Suit::Clubs)],
+        ];
+        let solve = |hands, leader, table: &[Card]| solve(hands, GameMode::Obenabe, leader, table);
+        assert_eq!(solve(hands.clone(), 7, &[]), Err(Error::NoSuchSeat(7)));
+        assert_eq!(
+            solve(hands.clone(), 1, &[card(Number::Ace, Suit::Hearts)]),
+            Err(Error::Deal(DealError::DuplicateCard(card(
+                Number::Ace,
+                Suit::Hearts
+            ))))
+        );
+        assert_eq!(
+            solve(hands.clone(), 0, &[card(Number::Six, Suit::Hearts)]),
+            Err(Error::UnevenHands { seat: 0, size: 1 })
+        );
+        assert_eq!(
+            solve(Default::default(), 0, &[]),
+            Err(Error::UnevenHands { seat: 0, size: 0 })
+        );
+        assert!(solve(hands, 2, &[]).is_ok());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::card::*;
+use crate::display_vec_cards;
+use crate::error::Error;
+use crate::game::*;
+use crate::record::Record;
+use std::io;
+
+//What can be typed while looking at a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Next,
+    Previous,
+    Fold(usize),
+    //The hands of a single seat, or all of them with None
+    Perspective(Option<usize>),
+    Quit,
+}
+
+impl Command {
+    //Enter alone goes forward, n/p step, a number jumps to that fold, s0 to s3 show a single
+    //hand, a shows all of them again and q quits
+    pub fn parse(answer: &str) -> Option<Command> {
+        let answer = answer.trim().to_lowercase();
+        match answer.as_str() {
+            "" | "n" => Some(Command::Next),
+            "p" | "b" => Some(Command::Previous),
+            "a" => Some(Command::Perspective(None)),
+            "q" => Some(Command::Quit),
+            _ => match answer.strip_prefix('s') {
+                Some(seat) => seat
+                    .parse()
+                    .ok()
+                    .filter(|&seat| seat < 4)
+                    .map(|seat| Command::Perspective(Some(seat))),
+                None => answer.parse().ok().map(Command::Fold),
+            },
+        }
+    }
+}
+
+//Walks through a recorded round fold by fold, step 0 being right after the trump choice
+pub struct Viewer<'a> {
+    record: &'a Record,
+    step: usize,
+    steps: usize,
+    perspective: Option<usize>,
+}
+
+impl<'a> Viewer<'a> {
+    pub fn new(record: &'a Record) -> Viewer<'a> {
+        //an unfinished fold is shown on the table at the last step
+        let steps = record.folds.iter().filter(|f| f.winner.is_some()).count();
+        Viewer {
+            record,
+            step: 0,
+            steps,
+            perspective: None,
+        }
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    //False once the viewer should be closed
+    pub fn apply(&mut self, command: Command) -> bool {
+        match command {
+            Command::Next => self.step = (self.step + 1).min(self.steps),
+            Command::Previous => self.step = self.step.saturating_sub(1),
+            Command::Fold(fold) => self.step = fold.min(self.steps),
+            Command::Perspective(seat) => self.perspective = seat,
+            Command::Quit => return false,
+        }
+        true
+    }
+
+    //The round as it is at the current step
+    pub fn game(&self) -> Result<GameState, Error> {
+        match self.record.folds.get(self.step) {
+            Some(fold) if fold.winner.is_none() => self.record.replay(),
+            _ => self.record.replay_to(self.step),
+        }
+    }
+
+    pub fn show(&self) -> Result<(), Error> {
+        let game = self.game()?;
+        println!();
+        println!(
+            "Fold {} of {}, {}",
+            self.step,
+            self.steps,
+            game.mode()
+                .map_or("no trump yet".to_string(), |m| m.to_string())
+        );
+        for seat in 0..4 {
+            if self.perspective.is_none() || self.perspective == Some(seat) {
+                print!("{:>8}: ", game.player(seat).name());
+                display_vec_cards(&game.player(seat).hand().to_vec());
+            }
+        }
+        if !game.table().is_empty() {
+            print!("On the table: ");
+            display_vec_cards(&game.table().iter().map(|x| x.card).collect::<Vec<Card>>());
+        } else if let Some(trick) = game.tricks().last() {
+            print!("Last fold: ");
+            display_vec_cards(&trick.cards.iter().map(|x| x.card).collect::<Vec<Card>>());
+            println!(
+                "won by {} for {} points",
+                game.player(trick.winner).name(),
+                trick.points
+            );
+        }
+        println!(
+            "points_ac: {}  points_bd: {}",
+            game.points(Team::AC),
+            game.points(Team::BD)
+        );
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> Result<(), Error> {
+        loop {
+            self.show()?;
+            println!("(Enter: next, p: previous, <n>: fold n, s0-s3: one hand, a: all, q: quit)");
+            let mut answer = String::new();
+            if io::stdin()
+                .read_line(&mut answer)
+                .map_err(|e| Error::Input(e.to_string()))?
+                == 0
+            {
+                return Ok(());
+            }
+            match Command::parse(&answer) {
+                Some(command) => {
+                    if !self.apply(command) {
+                        return Ok(());
+                    }
+                }
+                None => println!("Unknown command"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn record() -> Record {
+        let text = "jass-record 1\nplayer 0 A\nplayer 1 B\nplayer 2 C\nplayer 3 D\n\
+            deal 6789TJQKA♣ 6789TJQKA♦ 6789TJQKA♥ 6789TJQKA♠\nchooser 0\ntrump obenabe\n\
+            fold 0 A♣ A♦ A♥ A♠ -> 0 44\nfold 0 K♣ K♦";
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(Command::parse("\n"), Some(Command::Next));
+        assert_eq!(Command::parse("P"), Some(Command::Previous));
+        assert_eq!(Command::parse("7"), Some(Command::Fold(7)));
+        assert_eq!(Command::parse("s2"), Some(Command::Perspective(Some(2))));
+        assert_eq!(Command::parse("s4"), None);
+        assert_eq!(Command::parse("hello"), None);
+    }
+
+    #[test]
+    fn steps_through_the_folds() {
+        let record = record();
+        let mut viewer = Viewer::new(&record);
+        assert_eq!(viewer.game().unwrap().tricks().len(), 0);
+        viewer.apply(Command::Fold(9));
+        assert_eq!(viewer.step(), 1);
+        let game = viewer.game().unwrap();
+        assert_eq!(game.tricks().len(), 1);
+        assert_eq!(game.points(Team::AC), 44);
+        assert_eq!(game.table().len(), 2);
+        viewer.apply(Command::Previous);
+        assert_eq!(viewer.step(), 0);
+        assert!(!viewer.apply(Command::Quit));
+
+        let empty = Record::new(
+            Default::default(),
+            fixtures::sorted_deal(),
+            0,
+            Multipliers::default(),
+        );
+        let mut viewer = Viewer::new(&empty);
+        viewer.apply(Command::Next);
+        assert_eq!(viewer.step(), 0);
+    }
+}